@@ -0,0 +1,134 @@
+//! Precompiles the CEFR word lists into `$OUT_DIR` so the WASM module does
+//! not parse CSV the first time `analyze` runs.
+//!
+//! Outputs:
+//! - dictionary_index.fst: lookup key -> key ordinal, an index into GROUPS
+//! - phrase_index.fst:     the phrase automaton `PhraseMatcher` walks
+//! - dictionary_data.rs:   static ENTRIES (with their senses) / GROUPS / PHRASES
+//!   tables; ENTRIES is laid out key by key and GROUPS holds each key's
+//!   `(offset, count)` range of it
+//!
+//! All three files depend only on the CSV contents (keys are emitted in sorted
+//! order), so rebuilds are byte-for-byte reproducible.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use fst::MapBuilder;
+
+#[path = "src/dict_source.rs"]
+mod dict_source;
+
+use dict_source::{phrase_automaton, DictionaryBuilder, LevelSource, SourceEntry};
+
+/// Sources in priority order; earlier lists win ties in `select_best_entry`.
+/// Paths are relative to the crate; the vocabulary profiles live in the
+/// app's `stardict/` directory, which `CEFR_STARDICT_DIR` can override.
+const SOURCES: &[(LevelSource, &str)] = &[
+    (LevelSource::CefrJ, "$STARDICT/cefrj-vocabulary-profile-1.5.csv"),
+    (LevelSource::Octanove, "$STARDICT/octanove-vocabulary-profile-c1c2-1.0.csv"),
+    (LevelSource::Core, "assets/dictionary.csv"),
+];
+
+/// Default location of the vocabulary profiles, relative to the crate
+const STARDICT_DIR: &str = "../stardict";
+
+/// Per-sense levels of polysemous words, attached to the entries above.
+const SENSES: &str = "assets/senses.csv";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/dict_source.rs");
+
+    println!("cargo:rerun-if-env-changed=CEFR_STARDICT_DIR");
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set"));
+    let stardict = env::var("CEFR_STARDICT_DIR").map(PathBuf::from).unwrap_or_else(|_| manifest_dir.join(STARDICT_DIR));
+
+    let mut builder = DictionaryBuilder::new();
+    for (source, path) in SOURCES {
+        let file = match path.strip_prefix("$STARDICT/") {
+            Some(name) => stardict.join(name),
+            None => manifest_dir.join(path),
+        };
+        let csv = read_source(&file);
+        if let Err(e) = builder.add_csv(*source, &file.display().to_string(), &csv) {
+            panic!("{}", e);
+        }
+    }
+    let csv = read_source(&manifest_dir.join(SENSES));
+    if let Err(e) = builder.add_senses_csv(SENSES, &csv) {
+        panic!("{}", e);
+    }
+    let compiled = builder.finish();
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let out_dir = Path::new(&out_dir);
+
+    // Key index. Each key's entries are stored side by side, so a lookup is
+    // one slice of ENTRIES; the few entries filed under several spellings
+    // are written once per spelling.
+    let mut index = MapBuilder::memory();
+    let mut entries: Vec<&SourceEntry> = Vec::new();
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for (ordinal, (key, ids)) in compiled.index.iter().enumerate() {
+        index.insert(key, ordinal as u64).expect("dictionary keys must be sorted and unique");
+        groups.push((entries.len(), ids.len()));
+        entries.extend(ids.iter().map(|&id| &compiled.entries[id as usize]));
+    }
+    let index_bytes = index.into_inner().expect("failed to finish dictionary index");
+    fs::write(out_dir.join("dictionary_index.fst"), index_bytes).expect("failed to write dictionary index");
+
+    let phrase_bytes = phrase_automaton(compiled.phrases.iter().map(|(p, _)| p.as_str()));
+    fs::write(out_dir.join("phrase_index.fst"), phrase_bytes).expect("failed to write phrase index");
+
+    // Entry tables
+    let mut code = String::new();
+    writeln!(code, "// @generated by build.rs; do not edit.").unwrap();
+    writeln!(code, "static ENTRIES: [WordEntry; {}] = [", entries.len()).unwrap();
+    for e in entries {
+        let senses: Vec<String> = e.senses.iter().map(|s| format!(
+            "Sense {{ pos: {:?}, gloss: {:?}, level: CEFRLevel::{:?}, collocates: &{:?}, object: ObjectType::{:?} }}",
+            s.pos, s.gloss, s.level, s.collocates, s.object
+        )).collect();
+        writeln!(
+            code,
+            "    WordEntry {{ lemma: {:?}, pos: {:?}, level: CEFRLevel::{:?}, is_abstract: {}, source: LevelSource::{:?}, senses: &[{}] }},",
+            e.lemma, e.pos, e.level, e.is_abstract, e.source, senses.join(", ")
+        ).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+
+    writeln!(code, "static GROUPS: [(u32, u32); {}] = [", groups.len()).unwrap();
+    for chunk in groups.chunks(8) {
+        let line: Vec<String> = chunk.iter().map(|(offset, count)| format!("({}, {})", offset, count)).collect();
+        writeln!(code, "    {},", line.join(", ")).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+
+    writeln!(code, "static PHRASES: [(&str, CEFRLevel); {}] = [", compiled.phrases.len()).unwrap();
+    for (phrase, level) in &compiled.phrases {
+        writeln!(code, "    ({:?}, CEFRLevel::{:?}),", phrase, level).unwrap();
+    }
+    writeln!(code, "];").unwrap();
+
+    writeln!(code, "static INDEX_FST: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/dictionary_index.fst\"));").unwrap();
+    writeln!(code, "static PHRASE_FST: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/phrase_index.fst\"));").unwrap();
+    fs::write(out_dir.join("dictionary_data.rs"), code).expect("failed to write dictionary tables");
+}
+
+/// Read a dictionary source, registering it for rebuilds. A missing file is
+/// a setup problem, so say which file and how to point the build at it.
+fn read_source(path: &Path) -> String {
+    println!("cargo:rerun-if-changed={}", path.display());
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        panic!(
+            "cannot read dictionary source {}: {}\n\
+             The CEFR-J and Octanove vocabulary profiles are expected in the app's stardict/ \
+             directory next to cefr-core/; set CEFR_STARDICT_DIR to build from elsewhere.",
+            path.display(),
+            e
+        )
+    })
+}
@@ -5,8 +5,14 @@ use fst::{MapBuilder, Map};
 use byteorder::{WriteBytesExt, LittleEndian};
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use std::collections::BTreeMap;
 use serde_json::{Value, json};
 
+#[path = "../exchange.rs"]
+mod exchange;
+
+use exchange::parse_exchange;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("正在从 JSONL 构建 FST 词典...");
 
@@ -15,6 +21,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let input_path = resources_dir.join("dict_dump.jsonl");
     let output_fst_path = resources_dir.join("dict.fst");
     let output_data_path = resources_dir.join("dict.data");
+    let output_exchange_fst_path = resources_dir.join("exchange.fst");
+    let output_exchange_data_path = resources_dir.join("exchange.data");
 
     if !input_path.exists() {
         eprintln!("输入文件未找到: {:?}", input_path);
@@ -38,7 +46,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 定义结构体用于排序 (键, JSON行)
     struct Entry {
         key: String,
-        json: String
+        json: String,
+        exchange: String
     }
     
     println!("正在读取 JSONL...");
@@ -62,7 +71,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
              
              entries.push(Entry {
                  key: w.to_lowercase(),
-                 json: array_json.to_string() // 存储压缩后的行 (数组字符串)
+                 json: array_json.to_string(), // 存储压缩后的行 (数组字符串)
+                 exchange: exchange.to_string()
              });
         }
     }
@@ -79,8 +89,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut current_offset: u64 = 0;
     let mut written = 0;
     let mut count = 0;
+
+    // 词形索引: 单词 -> (所属原形列表, 自身的词形变化字段)
+    let mut exchange_index: BTreeMap<String, (Vec<String>, String)> = BTreeMap::new();
     
     for entry in entries {
+        if !entry.exchange.is_empty() {
+            let parsed = parse_exchange(&entry.exchange);
+            if let Some(lemma) = parsed.lemma.as_ref().map(|l| l.to_lowercase()) {
+                if lemma != entry.key {
+                    let lemmas = &mut exchange_index.entry(entry.key.clone()).or_default().0;
+                    if !lemmas.contains(&lemma) { lemmas.push(lemma); }
+                }
+            }
+            for (_, form) in &parsed.forms {
+                let form = form.to_lowercase();
+                if form == entry.key { continue; }
+                let lemmas = &mut exchange_index.entry(form).or_default().0;
+                if !lemmas.contains(&entry.key) { lemmas.push(entry.key.clone()); }
+            }
+            if !parsed.forms.is_empty() {
+                exchange_index.entry(entry.key.clone()).or_default().1 = parsed.forms_field();
+            }
+        }
+
         // 不压缩条目，仅写入原始 Array JSON
         let bytes = entry.json.as_bytes();
         let len = bytes.len() as u32;
@@ -116,7 +148,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // 删除未压缩文件
     std::fs::remove_file(&output_data_path)?;
+
+    // 词形索引 (不压缩, WASM 直接按偏移读取)
+    // 记录格式与 dict.data 相同: u32 长度 + JSON 数组 [原形列表, 词形变化字段]
+    println!("正在写入词形索引 ({} 个词)...", exchange_index.len());
+    let mut exchange_build = MapBuilder::new(BufWriter::new(File::create(&output_exchange_fst_path)?))?;
+    let mut exchange_writer = BufWriter::new(File::create(&output_exchange_data_path)?);
+    let mut exchange_offset: u64 = 0;
+    for (word, (lemmas, forms)) in &exchange_index {
+        let record = json!([lemmas, forms]).to_string();
+        let bytes = record.as_bytes();
+        exchange_writer.write_u32::<LittleEndian>(bytes.len() as u32)?;
+        exchange_writer.write_all(bytes)?;
+        exchange_build.insert(word, exchange_offset)?;
+        exchange_offset += 4 + bytes.len() as u64;
+    }
+    exchange_build.finish()?;
+    exchange_writer.flush()?;
     
-    println!("完成! 已创建 dict.fst 和 dict.data.gz");
+    println!("完成! 已创建 dict.fst, dict.data.gz, exchange.fst 和 exchange.data");
     Ok(())
 }
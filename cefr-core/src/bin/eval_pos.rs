@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use cefr_core::{evaluate_pos, PosEvaluation, TaggerMode};
+
+/// 默认列出的出错词数
+const DEFAULT_ERROR_WORDS: usize = 30;
+
+fn usage() -> ! {
+    eprintln!("用法: cargo run --bin eval_pos -- <金标准文件> [--tagger hmm|rules|rules-base] [--errors N] [--min-accuracy 0.95]");
+    eprintln!("金标准文件可为 CoNLL-U 或每行一句的 word/TAG 格式");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut gold_path = None;
+    let mut mode = TaggerMode::default();
+    let mut error_words = DEFAULT_ERROR_WORDS;
+    let mut min_accuracy = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tagger" => mode = args.next().and_then(|n| TaggerMode::from_name(&n)).unwrap_or_else(|| usage()),
+            "--errors" => error_words = args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage()),
+            "--min-accuracy" => min_accuracy = Some(args.next().and_then(|n| n.parse::<f64>().ok()).unwrap_or_else(|| usage())),
+            _ if gold_path.is_none() && !arg.starts_with("--") => gold_path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+    let gold_path = gold_path.unwrap_or_else(|| usage());
+
+    let text = std::fs::read_to_string(&gold_path)?;
+    let eval = evaluate_pos(&text, mode)?;
+    report(&eval, error_words);
+
+    // 供发布前检查: 准确率低于阈值时以非零状态退出
+    if let Some(min) = min_accuracy {
+        if eval.accuracy < min {
+            eprintln!("准确率 {:.2}% 低于要求的 {:.2}%", eval.accuracy * 100.0, min * 100.0);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn report(eval: &PosEvaluation, error_words: usize) {
+    println!("句子数: {}, 评测词元: {}, 未对齐: {}", eval.sentences, eval.tokens, eval.unaligned);
+    println!("准确率: {:.2}% ({}/{})", eval.accuracy * 100.0, eval.correct, eval.tokens);
+
+    println!("\n== 各标签 ==");
+    println!("{:<6} {:>9} {:>9} {:>8} {:>8}", "标签", "精确率", "召回率", "金标准", "预测");
+    for t in &eval.per_tag {
+        println!("{:<6} {:>8.1}% {:>8.1}% {:>8} {:>8}", t.tag, t.precision * 100.0, t.recall * 100.0, t.support, t.predicted);
+    }
+
+    // 混淆矩阵只列出出错涉及的标签: 行为金标准, 列为预测
+    println!("\n== 混淆矩阵 (行: 金标准, 列: 预测) ==");
+    let mut columns: Vec<&String> = eval.confusion.values().flat_map(|row| row.keys()).collect();
+    columns.sort();
+    columns.dedup();
+    print!("{:<6}", "");
+    for c in &columns {
+        print!("{:>6}", c);
+    }
+    println!();
+    for (gold, row) in &eval.confusion {
+        print!("{:<6}", gold);
+        for c in &columns {
+            match row.get(*c) {
+                Some(n) => print!("{:>6}", n),
+                None => print!("{:>6}", "."),
+            }
+        }
+        println!();
+    }
+
+    println!("\n== 出错最多的词 (前 {}) ==", error_words);
+    for w in eval.word_errors.iter().take(error_words) {
+        let confusions: Vec<String> = w.confusions.iter().map(|(g, p, n)| format!("{}->{} x{}", g, p, n)).collect();
+        println!("{:<16} {:>3}/{:<3} {}", w.word, w.errors, w.occurrences, confusions.join(", "));
+    }
+}
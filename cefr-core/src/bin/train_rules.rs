@@ -0,0 +1,96 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use cefr_core::{tag_gold_words, TaggerMode};
+
+#[path = "../brill.rs"]
+mod brill;
+#[path = "../corpus.rs"]
+mod corpus;
+
+use brill::{learn_rules, TrainingSentence};
+use corpus::parse_tagged_corpus;
+
+/// 最多学习的规则数
+const MAX_RULES: usize = 200;
+/// 规则至少要净修正的错误数
+const MIN_GAIN: usize = 2;
+
+/// 可由规则修改的位置中标注正确的比例
+fn accuracy(sentences: &[TrainingSentence]) -> f64 {
+    let (mut correct, mut total) = (0, 0);
+    for s in sentences {
+        for i in 0..s.tags.len() {
+            if !s.fixed[i] {
+                total += 1;
+                if s.tags[i] == s.gold[i] {
+                    correct += 1;
+                }
+            }
+        }
+    }
+    correct as f64 / total.max(1) as f64
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 路径配置: cargo run --bin train_rules [语料] [规则文件]
+    let mut args = std::env::args().skip(1);
+    let input_path = PathBuf::from(args.next().unwrap_or_else(|| "assets/pos_corpus.txt".to_string()));
+    let output_path = PathBuf::from(args.next().unwrap_or_else(|| "assets/pos_rules.txt".to_string()));
+
+    if !input_path.exists() {
+        eprintln!("语料文件未找到: {:?}", input_path);
+        std::process::exit(1);
+    }
+
+    println!("正在读取标注语料 {:?}...", input_path);
+    let gold = parse_tagged_corpus(&std::fs::read_to_string(&input_path)?)?;
+
+    // 初始标注: 不含已学规则的规则标注器; 分词不一致的位置保持金标准且不参与学习
+    println!("正在用规则标注器做初始标注 ({} 句)...", gold.len());
+    let mut sentences: Vec<TrainingSentence> = gold
+        .iter()
+        .map(|sentence| {
+            let words: Vec<String> = sentence.iter().map(|(w, _)| w.clone()).collect();
+            let predictions = tag_gold_words(&words, TaggerMode::RulesBase);
+            let (tags, fixed) = predictions
+                .iter()
+                .zip(sentence)
+                .map(|(p, (_, gold_tag))| match p {
+                    Some(p) => (p.tag.clone(), !p.rule_target),
+                    None => (gold_tag.clone(), true),
+                })
+                .unzip();
+            TrainingSentence {
+                words: words.iter().map(|w| w.to_lowercase()).collect(),
+                tags,
+                gold: sentence.iter().map(|(_, t)| t.clone()).collect(),
+                fixed,
+            }
+        })
+        .collect();
+
+    let before = accuracy(&sentences);
+    println!("初始准确率: {:.2}%", before * 100.0);
+
+    let learned = learn_rules(&mut sentences, MAX_RULES, MIN_GAIN);
+    for (i, l) in learned.iter().enumerate() {
+        println!("{:>4}. +{:<3} {}", i + 1, l.gain, l.rule);
+    }
+    println!("学习后准确率: {:.2}% ({} 条规则)", accuracy(&sentences) * 100.0, learned.len());
+
+    let mut writer = BufWriter::new(File::create(&output_path)?);
+    writeln!(writer, "# Brill transformation rules for the rule-based POS tagger (src/pos.rs),")?;
+    writeln!(writer, "# learned from {} by `cargo run --bin train_rules`.", input_path.display())?;
+    writeln!(writer, "# One rule per line, applied in order: `prev_tag=DT, tag=VB -> NN`.")?;
+    writeln!(writer, "# Conditions: prev_tag, next_tag, prev2_tag, next2_tag, word, prev_word, next_word;")?;
+    writeln!(writer, "# {} stands for beyond the sentence edge.", brill::BOUNDARY)?;
+    for l in &learned {
+        writeln!(writer, "{}", l.rule)?;
+    }
+    writer.flush()?;
+
+    println!("完成! 已写入 {:?}", output_path);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter};
+use std::path::PathBuf;
+
+#[path = "../corpus.rs"]
+mod corpus;
+#[path = "../hmm.rs"]
+mod hmm;
+
+use corpus::parse_tagged_line;
+use hmm::HmmModel;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 路径配置: cargo run --bin train_tagger [语料] [模型]
+    let mut args = std::env::args().skip(1);
+    let input_path = PathBuf::from(args.next().unwrap_or_else(|| "assets/pos_corpus.txt".to_string()));
+    let output_path = PathBuf::from(args.next().unwrap_or_else(|| "assets/pos_model.json".to_string()));
+
+    if !input_path.exists() {
+        eprintln!("语料文件未找到: {:?}", input_path);
+        std::process::exit(1);
+    }
+
+    println!("正在读取标注语料 {:?}...", input_path);
+    let reader = BufReader::new(File::open(&input_path)?);
+    let mut sentences = Vec::new();
+    for (line_no, line_res) in reader.lines().enumerate() {
+        let line = line_res?;
+        match parse_tagged_line(&line) {
+            Some(Ok(sentence)) => sentences.push(sentence),
+            Some(Err(e)) => {
+                eprintln!("第 {} 行格式错误: {}", line_no + 1, e);
+                std::process::exit(1);
+            }
+            None => {}
+        }
+    }
+    let token_count: usize = sentences.iter().map(Vec::len).sum();
+    println!("共 {} 句, {} 个词元", sentences.len(), token_count);
+
+    // 估计转移概率、词汇发射概率和后缀模型
+    let model = HmmModel::train(&sentences);
+    println!("标签集: {} 个标签", model.tags.len());
+
+    let writer = BufWriter::new(File::create(&output_path)?);
+    serde_json::to_writer(writer, &model)?;
+
+    println!("完成! 已写入 {:?}", output_path);
+    Ok(())
+}
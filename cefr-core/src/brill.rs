@@ -0,0 +1,289 @@
+//! Transformation-based (Brill) tag correction.
+//!
+//! A rule changes one tag into another when its context conditions hold,
+//! written one per line as `prev_tag=DT, tag=VB -> NN`. Rules apply in file
+//! order, each over the whole sentence. Shared by the library, which applies
+//! assets/pos_rules.txt after the rule-based tagger, and
+//! src/bin/train_rules.rs, which learns the rules from a gold corpus.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Tag and word seen beyond either end of the sentence.
+pub const BOUNDARY: &str = "<s>";
+
+/// What a rule condition looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    PrevTag,
+    NextTag,
+    Prev2Tag,
+    Next2Tag,
+    Word,
+    PrevWord,
+    NextWord,
+}
+
+impl Feature {
+    const ALL: [Feature; 7] = [
+        Feature::PrevTag,
+        Feature::NextTag,
+        Feature::Prev2Tag,
+        Feature::Next2Tag,
+        Feature::Word,
+        Feature::PrevWord,
+        Feature::NextWord,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Feature::PrevTag => "prev_tag",
+            Feature::NextTag => "next_tag",
+            Feature::Prev2Tag => "prev2_tag",
+            Feature::Next2Tag => "next2_tag",
+            Feature::Word => "word",
+            Feature::PrevWord => "prev_word",
+            Feature::NextWord => "next_word",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Feature> {
+        Feature::ALL.into_iter().find(|f| f.key() == key)
+    }
+
+    fn is_word(&self) -> bool {
+        matches!(self, Feature::Word | Feature::PrevWord | Feature::NextWord)
+    }
+
+    fn offset(&self) -> isize {
+        match self {
+            Feature::Prev2Tag => -2,
+            Feature::PrevTag | Feature::PrevWord => -1,
+            Feature::Word => 0,
+            Feature::NextTag | Feature::NextWord => 1,
+            Feature::Next2Tag => 2,
+        }
+    }
+
+    /// The feature's value at position `i`; words are compared lowercased.
+    fn value<'a>(&self, i: usize, words: &'a [String], tags: &'a [String]) -> &'a str {
+        let Some(j) = i.checked_add_signed(self.offset()).filter(|&j| j < tags.len()) else { return BOUNDARY };
+        if self.is_word() { &words[j] } else { &tags[j] }
+    }
+}
+
+/// Condition combinations the trainer instantiates from tagging errors.
+const TEMPLATES: &[&[Feature]] = &[
+    &[Feature::PrevTag],
+    &[Feature::NextTag],
+    &[Feature::Prev2Tag],
+    &[Feature::Next2Tag],
+    &[Feature::PrevTag, Feature::NextTag],
+    &[Feature::Prev2Tag, Feature::PrevTag],
+    &[Feature::NextTag, Feature::Next2Tag],
+    &[Feature::Word],
+    &[Feature::PrevWord],
+    &[Feature::NextWord],
+    &[Feature::Word, Feature::PrevTag],
+    &[Feature::Word, Feature::NextTag],
+    &[Feature::PrevWord, Feature::Word],
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub from: String,
+    pub to: String,
+    pub conditions: Vec<(Feature, String)>,
+}
+
+impl Rule {
+    fn matches(&self, i: usize, words: &[String], tags: &[String]) -> bool {
+        tags[i] == self.from && self.conditions.iter().all(|(f, v)| f.value(i, words, tags) == v)
+    }
+
+    /// Parse `prev_tag=DT, tag=VB -> NN`. Conditions are separated by ", "
+    /// so a comma tag still reads naturally (`prev_tag=,, tag=NN -> VB`).
+    pub fn parse(line: &str) -> Result<Rule, String> {
+        let (lhs, to) = line.split_once(" -> ").ok_or_else(|| format!("missing \" -> \" in {:?}", line))?;
+        let mut from = None;
+        let mut conditions = Vec::new();
+        for part in lhs.split(", ") {
+            let (key, value) = part.trim().split_once('=').ok_or_else(|| format!("malformed condition {:?}", part))?;
+            if key == "tag" {
+                from = Some(value.to_string());
+            } else {
+                let feature = Feature::from_key(key).ok_or_else(|| format!("unknown condition {:?}", key))?;
+                let value = if feature.is_word() { value.to_lowercase() } else { value.to_string() };
+                conditions.push((feature, value));
+            }
+        }
+        let from = from.ok_or_else(|| format!("missing tag= in {:?}", line))?;
+        Ok(Rule { from, to: to.trim().to_string(), conditions })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (feature, value) in &self.conditions {
+            write!(f, "{}={}, ", feature.key(), value)?;
+        }
+        write!(f, "tag={} -> {}", self.from, self.to)
+    }
+}
+
+/// Parse a rules file; blank lines and `#` comments are skipped.
+pub fn parse_rules(text: &str) -> Result<Vec<Rule>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| Rule::parse(line.trim()).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Apply `rules` in order. Each rule first finds all the positions it matches,
+/// then retags them, so a change doesn't feed the same rule further along.
+/// Positions marked `fixed` (punctuation, numbers, clitics) are never changed.
+pub fn apply_rules(rules: &[Rule], words: &[String], tags: &mut [String], fixed: &[bool]) {
+    for rule in rules {
+        apply_rule(rule, words, tags, fixed);
+    }
+}
+
+fn apply_rule(rule: &Rule, words: &[String], tags: &mut [String], fixed: &[bool]) -> Vec<usize> {
+    let hits: Vec<usize> = (0..tags.len()).filter(|&i| !fixed[i] && rule.matches(i, words, tags)).collect();
+    for &i in &hits {
+        tags[i] = rule.to.clone();
+    }
+    hits
+}
+
+/// One corpus sentence as the trainer sees it.
+pub struct TrainingSentence {
+    /// Lowercased words
+    pub words: Vec<String>,
+    /// Current tags, starting from the initial tagger's output
+    pub tags: Vec<String>,
+    pub gold: Vec<String>,
+    pub fixed: Vec<bool>,
+}
+
+/// A learned rule and how many errors it fixed on the training corpus.
+pub struct LearnedRule {
+    pub rule: Rule,
+    pub gain: usize,
+}
+
+/// Greedy transformation-based learning: instantiate the templates at every
+/// remaining error, keep the rule with the best fixes-minus-breakages, apply
+/// it and repeat until no rule gains `min_gain` or `max_rules` are learned.
+pub fn learn_rules(sentences: &mut [TrainingSentence], max_rules: usize, min_gain: usize) -> Vec<LearnedRule> {
+    let mut learned = Vec::new();
+
+    while learned.len() < max_rules {
+        // Candidate rules by the number of errors they'd fix
+        let mut fixes: HashMap<Rule, usize> = HashMap::new();
+        for s in sentences.iter() {
+            for i in 0..s.tags.len() {
+                if s.fixed[i] || s.tags[i] == s.gold[i] {
+                    continue;
+                }
+                for template in TEMPLATES {
+                    let conditions = template.iter().map(|f| (*f, f.value(i, &s.words, &s.tags).to_string())).collect();
+                    let rule = Rule { from: s.tags[i].clone(), to: s.gold[i].clone(), conditions };
+                    *fixes.entry(rule).or_default() += 1;
+                }
+            }
+        }
+
+        let mut candidates: Vec<(Rule, usize)> = fixes.into_iter().collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+
+        // Net gain; the fix count bounds it, so stop once that can't win
+        let mut best: Option<(Rule, usize)> = None;
+        for (rule, good) in candidates {
+            if good < min_gain || best.as_ref().is_some_and(|(_, gain)| good <= *gain) {
+                break;
+            }
+            let bad: usize = sentences
+                .iter()
+                .map(|s| {
+                    (0..s.tags.len())
+                        .filter(|&i| !s.fixed[i] && s.tags[i] == s.gold[i] && rule.matches(i, &s.words, &s.tags))
+                        .count()
+                })
+                .sum();
+            if good > bad && good - bad >= min_gain && best.as_ref().is_none_or(|(_, gain)| good - bad > *gain) {
+                best = Some((rule, good - bad));
+            }
+        }
+
+        let Some((rule, gain)) = best else { break };
+        for s in sentences.iter_mut() {
+            apply_rule(&rule, &s.words, &mut s.tags, &s.fixed);
+        }
+        learned.push(LearnedRule { rule, gain });
+    }
+
+    learned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rules_round_trip_through_the_text_format() {
+        let rule = Rule::parse("prev_tag=DT, word=Book, tag=VB -> NN").unwrap();
+        assert_eq!(rule.from, "VB");
+        assert_eq!(rule.to, "NN");
+        assert_eq!(rule.conditions, vec![(Feature::PrevTag, "DT".to_string()), (Feature::Word, "book".to_string())]);
+        assert_eq!(rule.to_string(), "prev_tag=DT, word=book, tag=VB -> NN");
+        assert_eq!(Rule::parse("prev_tag=,, tag=NN -> VB").unwrap().conditions, vec![(Feature::PrevTag, ",".to_string())]);
+    }
+
+    #[test]
+    fn bad_lines_report_their_line_number() {
+        assert_eq!(parse_rules("# comment\n\nprev_tag=DT, tag=VB -> NN\n").unwrap().len(), 1);
+        assert!(parse_rules("# comment\nprev_tag=DT, tag=VB NN").unwrap_err().starts_with("line 2:"));
+        assert!(parse_rules("color=red, tag=VB -> NN").unwrap_err().contains("unknown condition"));
+        assert!(parse_rules("prev_tag=DT -> NN").unwrap_err().contains("missing tag="));
+    }
+
+    #[test]
+    fn rules_respect_context_edges_and_fixed_positions() {
+        let words = strings(&["the", "man", "the", "police", "arrested"]);
+        let mut tags = strings(&["DT", "NN", "DT", "NNS", "VBN"]);
+        let rules = parse_rules("prev_tag=NNS, next_tag=<s>, tag=VBN -> VBD\nprev_tag=<s>, tag=DT -> NN").unwrap();
+        apply_rules(&rules, &words, &mut tags, &[false, false, false, false, false]);
+        assert_eq!(tags, strings(&["NN", "NN", "DT", "NNS", "VBD"]));
+
+        let mut tags = strings(&["DT", "NN", "DT", "NNS", "VBN"]);
+        apply_rules(&rules, &words, &mut tags, &[true, false, false, false, true]);
+        assert_eq!(tags, strings(&["DT", "NN", "DT", "NNS", "VBN"]));
+    }
+
+    #[test]
+    fn the_trainer_learns_the_rule_that_fixes_its_errors() {
+        let sentence = |words: &[&str], tags: &[&str], gold: &[&str]| TrainingSentence {
+            words: strings(words),
+            tags: strings(tags),
+            gold: strings(gold),
+            fixed: vec![false; words.len()],
+        };
+        let mut sentences = vec![
+            sentence(&["they", "book", "rooms"], &["PRP", "NN", "NNS"], &["PRP", "VBP", "NNS"]),
+            sentence(&["we", "book", "tables"], &["PRP", "NN", "NNS"], &["PRP", "VBP", "NNS"]),
+            sentence(&["a", "book", "."], &["DT", "NN", "."], &["DT", "NN", "."]),
+        ];
+        let learned = learn_rules(&mut sentences, 10, 2);
+        assert_eq!(learned.len(), 1);
+        assert_eq!((learned[0].rule.from.as_str(), learned[0].rule.to.as_str()), ("NN", "VBP"));
+        assert_eq!(learned[0].gain, 2);
+        assert!(sentences.iter().all(|s| s.tags == s.gold));
+    }
+}
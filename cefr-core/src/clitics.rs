@@ -0,0 +1,209 @@
+use crate::lemmatizer::is_past_form;
+use crate::pos::TaggedToken;
+use crate::tokenizer::TokenKind;
+
+/// What an `'s` or `'d` clitic stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clitic {
+    Is,
+    Has,
+    Had,
+    Would,
+    Possessive,
+}
+
+impl Clitic {
+    /// The clitic as written (after normalization).
+    pub fn surface(&self) -> &'static str {
+        match self {
+            Clitic::Is | Clitic::Has | Clitic::Possessive => "'s",
+            Clitic::Had | Clitic::Would => "'d",
+        }
+    }
+
+    /// "is", "has", "had", "would" or "possessive".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Clitic::Is => "is",
+            Clitic::Has => "has",
+            Clitic::Had => "had",
+            Clitic::Would => "would",
+            Clitic::Possessive => "possessive",
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Clitic::Is | Clitic::Has => "VBZ",
+            Clitic::Had => "VBD",
+            Clitic::Would => "MD",
+            Clitic::Possessive => "POS",
+        }
+    }
+}
+
+/// Hosts after which `'s` is always a verb: pronouns, wh-words and
+/// existential/locative "there"/"here" ("it's", "where's", "there's").
+fn is_verbal_host(host: &TaggedToken) -> bool {
+    host.tag.starts_with("PRP") && host.tag != "PRP$"
+        || matches!(host.word.to_lowercase().as_str(),
+            "it" | "he" | "she" | "that" | "this" | "what" | "who" | "where" | "when" | "how" | "why"
+            | "there" | "here" | "everyone" | "everything" | "everybody" | "someone" | "something"
+            | "somebody" | "nobody" | "nothing" | "one")
+}
+
+/// Whether a token can start a noun phrase (an object after "has finished").
+fn starts_noun_phrase(token: Option<&TaggedToken>) -> bool {
+    token.is_some_and(|t| matches!(t.tag.as_str(), "DT" | "PRP" | "PRP$" | "CD") || t.tag.starts_with("NN"))
+}
+
+/// First word after the clitic, skipping adverbs ("he's never been", "I'd rather").
+fn next_verb_slot(next: &[TaggedToken]) -> usize {
+    next.iter()
+        .position(|t| {
+            let lower = t.word.to_lowercase();
+            !(t.tag == "RB" || matches!(lower.as_str(), "not" | "never" | "just" | "already" | "also" | "really"))
+        })
+        .unwrap_or(next.len())
+}
+
+/// is vs has: has before a participle ("she's gone", "he's finished the book"),
+/// but not before a participial adjective ("she's tired").
+fn is_or_has(next: &[TaggedToken]) -> Clitic {
+    let slot = next_verb_slot(next);
+    let Some(word) = next.get(slot).filter(|t| t.is_word()) else { return Clitic::Is };
+    let lower = word.word.to_lowercase();
+
+    if matches!(lower.as_str(), "been" | "got" | "had") {
+        return Clitic::Has;
+    }
+    if is_past_form(&lower) {
+        // Regular -ed forms are often adjectives; need an object to read as "has"
+        let irregular = !lower.ends_with("ed");
+        if irregular || starts_noun_phrase(next.get(slot + 1)) {
+            return Clitic::Has;
+        }
+    }
+    Clitic::Is
+}
+
+/// Resolve `'s` from its host and the following tokens.
+fn resolve_s(host: Option<&TaggedToken>, next: &[TaggedToken]) -> Clitic {
+    if host.is_none_or(is_verbal_host) {
+        return is_or_has(next);
+    }
+
+    // After a noun: possessive unless a verb reading is clearly signalled
+    let Some(first) = next.first().filter(|t| t.is_word()) else { return Clitic::Possessive };
+    let lower = first.word.to_lowercase();
+    let verbal = matches!(lower.as_str(), "not" | "been" | "got" | "a" | "an" | "going" | "gone")
+        || matches!(first.tag.as_str(), "IN" | "RB" | "TO" | "VBG" | "VBN")
+        || lower.ends_with("ing") && first.tag.starts_with("VB");
+    if verbal {
+        is_or_has(next)
+    } else {
+        Clitic::Possessive
+    }
+}
+
+/// Resolve `'d`: had before a participle or "better", would otherwise
+/// ("I'd like", "she'd go", "we'd rather").
+fn resolve_d(next: &[TaggedToken]) -> Clitic {
+    // "better" is often tagged RB, which `next_verb_slot` would skip
+    if next.first().is_some_and(|t| t.word.eq_ignore_ascii_case("better")) {
+        return Clitic::Had;
+    }
+    let slot = next_verb_slot(next);
+    let Some(word) = next.get(slot).filter(|t| t.is_word()) else { return Clitic::Would };
+    let lower = word.word.to_lowercase();
+
+    if lower == "been" {
+        return Clitic::Had;
+    }
+    if matches!(lower.as_str(), "like" | "love" | "prefer" | "rather" | "have" | "be") {
+        return Clitic::Would;
+    }
+    if is_past_form(&lower) {
+        Clitic::Had
+    } else {
+        Clitic::Would
+    }
+}
+
+/// Replace each clitic token with its reading. Verb readings become words
+/// ("'s" -> "is", tagged VBZ) so later stages look up and count them like any
+/// verb; possessive `'s` stays a non-word token tagged POS.
+pub fn resolve_clitics(tokens: &mut [TaggedToken]) {
+    for i in 0..tokens.len() {
+        if tokens[i].kind != TokenKind::Clitic {
+            continue;
+        }
+        let host = i.checked_sub(1).map(|h| &tokens[h]);
+        let next = &tokens[i + 1..];
+        let clitic = if tokens[i].word.eq_ignore_ascii_case("'d") {
+            resolve_d(next)
+        } else {
+            resolve_s(host, next)
+        };
+
+        let token = &mut tokens[i];
+        token.tag = clitic.tag().to_string();
+        token.clitic = Some(clitic);
+        if clitic != Clitic::Possessive {
+            token.word = clitic.name().to_string();
+            token.kind = TokenKind::Word;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    /// (word, tag, clitic reading) of each clitic in `text`
+    fn clitics(text: &str) -> Vec<(String, String, &'static str)> {
+        tag_sentence(&tokenize_sentence(text), TaggerMode::default())
+            .into_iter()
+            .filter_map(|t| t.clitic.map(|c| (t.word, t.tag, c.name())))
+            .collect()
+    }
+
+    fn readings(text: &str) -> Vec<&'static str> {
+        clitics(text).into_iter().map(|(_, _, c)| c).collect()
+    }
+
+    #[test]
+    fn s_after_a_noun_is_possessive_unless_a_verb_follows() {
+        assert_eq!(readings("John's dog barked."), vec!["possessive"]);
+        assert_eq!(readings("The dog's bowl is empty."), vec!["possessive"]);
+        assert_eq!(readings("John's not here."), vec!["is"]);
+        assert_eq!(readings("John's been ill."), vec!["has"]);
+    }
+
+    #[test]
+    fn s_after_pronouns_and_wh_words_is_a_verb() {
+        assert_eq!(readings("Where's the station?"), vec!["is"]);
+        assert_eq!(readings("It's raining."), vec!["is"]);
+        assert_eq!(readings("She's gone home."), vec!["has"]);
+        assert_eq!(readings("She's tired."), vec!["is"]);
+        assert_eq!(readings("He's finished the book."), vec!["has"]);
+    }
+
+    #[test]
+    fn d_is_had_before_a_participle_and_would_otherwise() {
+        assert_eq!(readings("I'd like tea."), vec!["would"]);
+        assert_eq!(readings("She'd go if asked."), vec!["would"]);
+        assert_eq!(readings("We'd already left."), vec!["had"]);
+        assert_eq!(readings("You'd better hurry."), vec!["had"]);
+    }
+
+    #[test]
+    fn verb_readings_become_tagged_words() {
+        let found = clitics("It's late and I'd go.");
+        assert_eq!(found[0], ("is".to_string(), "VBZ".to_string(), "is"));
+        assert_eq!(found[1], ("would".to_string(), "MD".to_string(), "would"));
+        let possessive = clitics("Tom's car.");
+        assert_eq!(possessive[0], ("'s".to_string(), "POS".to_string(), "possessive"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Reader for gold-tagged corpora such as assets/pos_corpus.txt.
+//!
+//! Shared by the library (POS evaluation) and the training binaries in src/bin.
+#![allow(dead_code)]
+
+/// A sentence of (word, Penn Treebank tag) pairs.
+pub type TaggedSentence = Vec<(String, String)>;
+
+/// Parse one corpus line of whitespace-separated `word/TAG` tokens. Blank
+/// lines and `#` comments yield `None`, though a line opening with the token
+/// "#/#" is a sentence; the tag follows the last slash so words like "and/or"
+/// survive.
+pub fn parse_tagged_line(line: &str) -> Option<Result<TaggedSentence, String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') && !line.starts_with("#/") {
+        return None;
+    }
+    Some(
+        line.split_whitespace()
+            .map(|item| match item.rsplit_once('/') {
+                Some((word, tag)) if !word.is_empty() && !tag.is_empty() => Ok((word.to_string(), tag.to_string())),
+                _ => Err(format!("malformed token {:?}", item)),
+            })
+            .collect(),
+    )
+}
+
+/// Parse a whole `word/TAG` corpus; errors carry the 1-based line number.
+pub fn parse_tagged_corpus(text: &str) -> Result<Vec<TaggedSentence>, String> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| parse_tagged_line(line).map(|r| r.map_err(|e| format!("line {}: {}", i + 1, e))))
+        .collect()
+}
+
+/// Sentence text for gold words: space-separated, except that clitics
+/// ("'s", "'d") and Treebank's split negation ("do n't") attach to the word
+/// before, as the tokenizer expects. Also returns each word's byte offset.
+pub fn sentence_text<S: AsRef<str>>(words: &[S]) -> (String, Vec<usize>) {
+    let mut text = String::new();
+    let mut starts = Vec::with_capacity(words.len());
+    for word in words {
+        let word = word.as_ref();
+        let attached = word.starts_with('\'') && word.len() > 1 || word.eq_ignore_ascii_case("n't");
+        if !text.is_empty() && !attached {
+            text.push(' ');
+        }
+        starts.push(text.len());
+        text.push_str(word);
+    }
+    (text, starts)
+}
+
+/// Parse CoNLL-U: one token per line in ten tab-separated columns, with blank
+/// lines between sentences. The tag is the XPOS column, which holds Penn
+/// Treebank tags in the English treebanks. Multiword-token ranges ("1-2") and
+/// empty nodes ("1.1") are skipped.
+pub fn parse_conllu(text: &str) -> Result<Vec<TaggedSentence>, String> {
+    let mut sentences = Vec::new();
+    let mut current = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            if !current.is_empty() {
+                sentences.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 5 {
+            return Err(format!("line {}: expected 10 tab-separated columns", i + 1));
+        }
+        if columns[0].contains(['-', '.']) {
+            continue;
+        }
+        let (form, xpos) = (columns[1], columns[4]);
+        if xpos == "_" {
+            return Err(format!("line {}: no XPOS tag for {:?}", i + 1, form));
+        }
+        current.push((form.to_string(), xpos.to_string()));
+    }
+    if !current.is_empty() {
+        sentences.push(current);
+    }
+    Ok(sentences)
+}
+
+/// Parse a gold corpus in either format: CoNLL-U if its first data line is
+/// tab-separated, `word/TAG` otherwise.
+pub fn parse_gold(text: &str) -> Result<Vec<TaggedSentence>, String> {
+    let first = text.lines().map(str::trim_end).find(|l| !l.is_empty() && !l.starts_with('#'));
+    if first.is_some_and(|l| l.contains('\t')) {
+        parse_conllu(text)
+    } else {
+        parse_tagged_corpus(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_lines_split_on_the_last_slash() {
+        let corpus = "# comment\n\nand/or/CC 5/CD\n#/# 5/CD ./.\n";
+        let sentences = parse_tagged_corpus(corpus).unwrap();
+        assert_eq!(sentences.len(), 2);
+        assert_eq!(sentences[0][0], ("and/or".to_string(), "CC".to_string()));
+        assert_eq!(sentences[1][0], ("#".to_string(), "#".to_string()));
+        assert_eq!(parse_tagged_corpus("ok/JJ\nbad/ /NN").unwrap_err(), "line 2: malformed token \"bad/\"");
+    }
+
+    #[test]
+    fn conllu_reads_xpos_and_skips_ranges() {
+        let text = "# text = I don't know.\n\
+            1\tI\tI\tPRON\tPRP\t_\t3\tnsubj\t_\t_\n\
+            2-3\tdon't\t_\t_\t_\t_\t_\t_\t_\t_\n\
+            2\tdo\tdo\tAUX\tVBP\t_\t4\taux\t_\t_\n\
+            3\tn't\tnot\tPART\tRB\t_\t4\tadvmod\t_\t_\n\
+            4\tknow\tknow\tVERB\tVB\t_\t0\troot\t_\t_\n\
+            \n\
+            1\tYes\tyes\tINTJ\tUH\t_\t0\troot\t_\t_\n";
+        let sentences = parse_gold(text).unwrap();
+        assert_eq!(sentences.len(), 2);
+        let tags: Vec<&str> = sentences[0].iter().map(|(_, t)| t.as_str()).collect();
+        assert_eq!(tags, ["PRP", "VBP", "RB", "VB"]);
+        assert!(parse_conllu("1\tI\tI\tPRON\t_\t_\n").unwrap_err().contains("no XPOS"));
+    }
+
+    #[test]
+    fn clitics_and_negation_attach_to_the_word_before() {
+        let (text, starts) = sentence_text(&["John", "'s", "car", "does", "n't", "start", "'"]);
+        assert_eq!(text, "John's car doesn't start '");
+        assert_eq!(starts, [0, 4, 7, 11, 15, 19, 25]);
+    }
+}
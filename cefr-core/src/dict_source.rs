@@ -0,0 +1,534 @@
+//! CSV ingestion for the CEFR word lists.
+//!
+//! This file is compiled twice: by build.rs, which runs `DictionaryBuilder`
+//! to precompile the embedded dictionary, and by the library, which only
+//! needs the shared types and helpers.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CEFRLevel {
+    A1, A2, B1, B2, C1, C2, Unknown
+}
+
+impl CEFRLevel {
+    pub fn from_str(s: &str) -> Self {
+        match s.trim().to_uppercase().as_str() {
+            "A1" => CEFRLevel::A1,
+            "A2" => CEFRLevel::A2,
+            "B1" => CEFRLevel::B1,
+            "B2" => CEFRLevel::B2,
+            "C1" => CEFRLevel::C1,
+            "C2" => CEFRLevel::C2,
+            _ => CEFRLevel::Unknown,
+        }
+    }
+}
+
+/// Which word list a `WordEntry`'s level came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelSource {
+    /// assets/dictionary.csv (inflected forms, no POS)
+    Core,
+    /// CEFR-J Vocabulary Profile 1.5 (A1-B2)
+    CefrJ,
+    /// Octanove Vocabulary Profile C1/C2 1.0
+    Octanove,
+}
+
+impl LevelSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LevelSource::Core => "core",
+            LevelSource::CefrJ => "cefr-j",
+            LevelSource::Octanove => "octanove",
+        }
+    }
+}
+
+/// What a verb sense expects after it: "run fast" takes no object, "run a
+/// company" takes a thing, "convince someone" a person.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    /// No preference (also used for non-verb senses)
+    Any,
+    /// Intransitive use
+    None,
+    Person,
+    Thing,
+}
+
+impl ObjectType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "any" => Some(ObjectType::Any),
+            "none" => Some(ObjectType::None),
+            "person" => Some(ObjectType::Person),
+            "thing" => Some(ObjectType::Thing),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectType::Any => "any",
+            ObjectType::None => "none",
+            ObjectType::Person => "person",
+            ObjectType::Thing => "thing",
+        }
+    }
+}
+
+/// Suffixes that mark a noun as abstract when the source has no explicit flag.
+const ABSTRACT_SUFFIXES: &[&str] = &[
+    "tion", "sion", "ment", "ness", "ity", "ism", "ance", "ence", "hood", "ship", "dom",
+];
+
+pub fn has_abstract_suffix(word: &str) -> bool {
+    let lower = word.to_lowercase();
+    ABSTRACT_SUFFIXES.iter().any(|suffix| lower.len() > suffix.len() + 2 && lower.ends_with(suffix))
+}
+
+/// Errors raised while loading a dictionary source.
+#[derive(Debug)]
+pub enum DictionaryError {
+    /// The header lacks a column the loader cannot do without.
+    MissingColumn { source: String, column: &'static str },
+    /// A data line could not be parsed.
+    Malformed { source: String, line: usize, reason: String },
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DictionaryError::MissingColumn { source, column } => {
+                write!(f, "{}: missing required column '{}'", source, column)
+            }
+            DictionaryError::Malformed { source, line, reason } => {
+                write!(f, "{}:{}: {}", source, line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+/// Column positions resolved from a CSV header.
+/// `word` and `level` are required; everything else is optional.
+struct Schema {
+    word: usize,
+    lemma: Option<usize>,
+    pos: Option<usize>,
+    level: usize,
+    is_abstract: Option<usize>,
+}
+
+impl Schema {
+    fn from_header(header: &[String], source: &str) -> Result<Self, DictionaryError> {
+        let find = |names: &[&str]| {
+            header.iter().position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+        };
+        let missing = |column| DictionaryError::MissingColumn { source: source.to_string(), column };
+
+        Ok(Schema {
+            // Older merged exports key rows by `lemma` alone
+            word: find(&["word", "headword"]).or_else(|| find(&["lemma"])).ok_or_else(|| missing("word"))?,
+            lemma: find(&["lemma"]),
+            pos: find(&["pos"]),
+            level: find(&["level", "cefr"]).ok_or_else(|| missing("level"))?,
+            is_abstract: find(&["is_abstract", "abstract"]),
+        })
+    }
+}
+
+/// Split one CSV record into fields (RFC 4180 quoting: `"a, b"` and `""` escapes).
+fn parse_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Map the part-of-speech names used by the vocabulary profiles onto the
+/// short tags `select_best_entry` compares against.
+pub fn normalize_pos(raw: &str) -> String {
+    let p = raw.trim().to_lowercase();
+    match p.as_str() {
+        "" => String::new(),
+        "n" | "noun" => "noun".to_string(),
+        "v" | "verb" | "vern" | "be-verb" | "do-verb" | "have-verb" => "verb".to_string(),
+        "j" | "adj" | "adjective" => "adj".to_string(),
+        "r" | "adv" | "adverb" => "adv".to_string(),
+        "det" | "determiner" => "determiner".to_string(),
+        "prep" | "preposition" => "prep".to_string(),
+        "conj" | "conjunction" => "conj".to_string(),
+        "pron" | "pronoun" => "pronoun".to_string(),
+        "modal" | "modal auxiliary" => "modal".to_string(),
+        "to" | "infinitive-to" => "to".to_string(),
+        "num" | "number" => "num".to_string(),
+        "interj" | "interjection" => "interj".to_string(),
+        _ => p,
+    }
+}
+
+/// Split a slash-separated headword ("OK/okay", "a.m./A.M./am/AM") into its
+/// spellings. The first one is the canonical headword.
+pub fn split_variants(headword: &str) -> Vec<&str> {
+    headword.split('/').map(str::trim).filter(|v| !v.is_empty()).collect()
+}
+
+/// One dictionary row after normalization.
+#[derive(Debug, Clone)]
+pub struct SourceEntry {
+    pub lemma: String,
+    pub pos: String,
+    pub level: CEFRLevel,
+    pub is_abstract: bool,
+    pub source: LevelSource,
+    /// Leveled senses of a polysemous word, default sense first
+    pub senses: Vec<SourceSense>,
+}
+
+/// One row of the sense list: a meaning of (lemma, pos) with its own level
+/// and the context cues that pick it.
+#[derive(Debug, Clone)]
+pub struct SourceSense {
+    pub lemma: String,
+    pub pos: String,
+    pub gloss: String,
+    pub level: CEFRLevel,
+    /// Lowercased lemmas that signal the sense when they occur nearby
+    pub collocates: Vec<String>,
+    pub object: ObjectType,
+}
+
+/// Output of `DictionaryBuilder::finish`, serialized by build.rs.
+pub struct CompiledDictionary {
+    pub entries: Vec<SourceEntry>,
+    /// Lookup key -> indices into `entries`; every spelling of a headword shares its entries
+    pub index: BTreeMap<String, Vec<u32>>,
+    pub phrases: Vec<(String, CEFRLevel)>,
+}
+
+/// Incrementally merges several CSV vocabulary lists into one `Dictionary`.
+/// Entries are kept in insertion order, so sources added first win ties in `select_best_entry`.
+pub struct DictionaryBuilder {
+    entries: Vec<SourceEntry>,
+    /// Canonical headwords, exactly as listed
+    direct: HashMap<String, Vec<usize>>,
+    /// Alternative spellings and lowercased forms; ranked after direct entries
+    /// so "am" stays the verb rather than the "a.m./A.M./am/AM" variant
+    variants: HashMap<String, Vec<usize>>,
+    phrases: Vec<(String, CEFRLevel)>,
+    seen_phrases: HashSet<String>,
+    senses: Vec<SourceSense>,
+}
+
+impl DictionaryBuilder {
+    pub fn new() -> Self {
+        DictionaryBuilder {
+            entries: Vec::new(),
+            direct: HashMap::new(),
+            variants: HashMap::new(),
+            phrases: Vec::new(),
+            seen_phrases: HashSet::new(),
+            senses: Vec::new(),
+        }
+    }
+
+    /// Ingest CSV text, resolving columns by header name.
+    /// `name` only appears in error messages.
+    pub fn add_csv(&mut self, source: LevelSource, name: &str, csv_content: &str) -> Result<&mut Self, DictionaryError> {
+        let mut lines = csv_content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let header = match lines.next() {
+            Some((_, header)) => parse_csv_line(header.trim_start_matches('\u{feff}'))
+                .map_err(|reason| DictionaryError::Malformed { source: name.to_string(), line: 1, reason })?,
+            None => return Err(DictionaryError::Malformed {
+                source: name.to_string(), line: 1, reason: "empty file".to_string(),
+            }),
+        };
+        let schema = Schema::from_header(&header, name)?;
+
+        for (idx, line) in lines {
+            let malformed = |reason: String| DictionaryError::Malformed { source: name.to_string(), line: idx + 1, reason };
+            let fields = parse_csv_line(line).map_err(malformed)?;
+            let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or("");
+            let optional = |i: Option<usize>| i.map(field).filter(|f| !f.is_empty());
+
+            let spellings = split_variants(field(schema.word));
+            let Some(&word) = spellings.first() else {
+                return Err(malformed(format!("empty '{}' column", header[schema.word])));
+            };
+            let lemma = optional(schema.lemma)
+                .and_then(|l| split_variants(l).first().copied())
+                .unwrap_or(word)
+                .to_string();
+            let pos = normalize_pos(optional(schema.pos).unwrap_or(""));
+            let level = CEFRLevel::from_str(field(schema.level));
+            let is_abstract = match optional(schema.is_abstract) {
+                Some(flag) => matches!(flag.to_lowercase().as_str(), "true" | "1" | "yes"),
+                // No explicit flag: fall back to noun morphology
+                None => (pos.is_empty() || pos == "noun") && has_abstract_suffix(&lemma),
+            };
+
+            // Logic to handle Phrases (first source to list a phrase sets its level)
+            for spelling in spellings.iter().filter(|s| s.contains(' ')) {
+                if self.seen_phrases.insert(spelling.to_lowercase()) {
+                    self.phrases.push((spelling.to_string(), level.clone()));
+                }
+            }
+
+            let id = self.entries.len();
+            self.entries.push(SourceEntry {
+                lemma,
+                pos,
+                level,
+                is_abstract,
+                source,
+                senses: Vec::new(),
+            });
+
+            // Keys keep the CSV casing; lowercased copies let "Internet" or
+            // "OK" match the lowercased query that lookup falls back to.
+            self.direct.entry(word.to_string()).or_default().push(id);
+            let mut keys: Vec<String> = spellings.iter()
+                .flat_map(|s| [s.to_string(), s.to_lowercase()])
+                .filter(|k| k != word)
+                .collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                self.variants.entry(key).or_default().push(id);
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Ingest a sense list with columns `lemma`, `pos`, `sense`, `level` and
+    /// optionally `collocates` (separated by `;`) and `object`
+    /// (none/person/thing). Rows for the same word keep their file order.
+    pub fn add_senses_csv(&mut self, name: &str, csv_content: &str) -> Result<&mut Self, DictionaryError> {
+        let mut lines = csv_content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let header = match lines.next() {
+            Some((_, header)) => parse_csv_line(header.trim_start_matches('\u{feff}'))
+                .map_err(|reason| DictionaryError::Malformed { source: name.to_string(), line: 1, reason })?,
+            None => return Ok(self),
+        };
+        let find = |column: &'static str| {
+            header.iter().position(|h| h.trim().eq_ignore_ascii_case(column))
+        };
+        let require = |column: &'static str| {
+            find(column).ok_or_else(|| DictionaryError::MissingColumn { source: name.to_string(), column })
+        };
+        let (lemma_col, pos_col, gloss_col, level_col) = (require("lemma")?, require("pos")?, require("sense")?, require("level")?);
+        let (collocates_col, object_col) = (find("collocates"), find("object"));
+
+        for (idx, line) in lines {
+            let malformed = |reason: String| DictionaryError::Malformed { source: name.to_string(), line: idx + 1, reason };
+            let fields = parse_csv_line(line).map_err(malformed)?;
+            let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or("");
+
+            let level = CEFRLevel::from_str(field(level_col));
+            if level == CEFRLevel::Unknown {
+                return Err(malformed(format!("unknown level {:?}", field(level_col))));
+            }
+            let object = object_col.map(field).unwrap_or("");
+            let object = ObjectType::from_str(object).ok_or_else(|| malformed(format!("unknown object type {:?}", object)))?;
+            let collocates = collocates_col
+                .map(field)
+                .unwrap_or("")
+                .split(';')
+                .map(|c| c.trim().to_lowercase())
+                .filter(|c| !c.is_empty())
+                .collect();
+
+            self.senses.push(SourceSense {
+                lemma: field(lemma_col).to_lowercase(),
+                pos: normalize_pos(field(pos_col)),
+                gloss: field(gloss_col).to_string(),
+                level,
+                collocates,
+                object,
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// Resolve the key index: direct entries first, then variant spellings.
+    /// Keys come out sorted, ready for an fst builder.
+    /// Senses attach to entries of their lemma with the same POS; entries
+    /// without a POS (inflection list) take every sense of the lemma.
+    pub fn finish(mut self) -> CompiledDictionary {
+        for entry in &mut self.entries {
+            let lemma = entry.lemma.to_lowercase();
+            entry.senses = self.senses.iter()
+                .filter(|s| s.lemma == lemma && (entry.pos.is_empty() || s.pos == entry.pos))
+                .cloned()
+                .collect();
+        }
+
+        let mut index: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for (key, ids) in self.direct {
+            index.insert(key, ids.into_iter().map(|id| id as u32).collect());
+        }
+        for (key, ids) in self.variants {
+            let slot = index.entry(key).or_default();
+            for id in ids.into_iter().map(|id| id as u32) {
+                if !slot.contains(&id) {
+                    slot.push(id);
+                }
+            }
+        }
+
+        CompiledDictionary {
+            entries: self.entries,
+            index,
+            phrases: self.phrases,
+        }
+    }
+}
+
+/// The automaton `PhraseMatcher` walks: an fst from each phrase's words,
+/// ASCII-lowercased and joined by single spaces, to its index in `phrases`.
+/// Two phrases that normalize alike keep the first index.
+pub fn phrase_automaton<'a>(phrases: impl IntoIterator<Item = &'a str>) -> Vec<u8> {
+    let mut keys: BTreeMap<String, u64> = BTreeMap::new();
+    for (id, phrase) in phrases.into_iter().enumerate() {
+        let words: Vec<String> = phrase.split_whitespace().map(|w| w.to_ascii_lowercase()).collect();
+        if !words.is_empty() {
+            keys.entry(words.join(" ")).or_insert(id as u64);
+        }
+    }
+    fst::Map::from_iter(keys).expect("phrase keys are sorted and unique").into_fst().into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(csv: &str) -> CompiledDictionary {
+        let mut builder = DictionaryBuilder::new();
+        builder.add_csv(LevelSource::Core, "test.csv", csv).unwrap();
+        builder.finish()
+    }
+
+    fn entries_for<'a>(dict: &'a CompiledDictionary, key: &str) -> Vec<&'a SourceEntry> {
+        dict.index.get(key).map(|ids| ids.iter().map(|&id| &dict.entries[id as usize]).collect()).unwrap_or_default()
+    }
+
+    #[test]
+    fn columns_are_resolved_by_header_name() {
+        let dict = build("level,pos,word,is_abstract\nB2,noun,freedom,true\nA1,verb,go,false\n");
+        let freedom = entries_for(&dict, "freedom");
+        assert_eq!(freedom.len(), 1);
+        assert_eq!(freedom[0].level, CEFRLevel::B2);
+        assert_eq!(freedom[0].pos, "noun");
+        assert!(freedom[0].is_abstract);
+        assert_eq!(entries_for(&dict, "go")[0].level, CEFRLevel::A1);
+    }
+
+    #[test]
+    fn header_aliases_and_bom_are_accepted() {
+        let dict = build("\u{feff}Headword,CEFR\nhouse,A1\n");
+        assert_eq!(entries_for(&dict, "house")[0].level, CEFRLevel::A1);
+    }
+
+    #[test]
+    fn quoted_fields_keep_commas_and_escaped_quotes() {
+        assert_eq!(parse_csv_line(r#"a,"b, c","say ""hi""""#).unwrap(), vec!["a", "b, c", r#"say "hi""#]);
+        let dict = build("word,pos,level\n\"well, well\",phrase,B1\n");
+        assert_eq!(dict.phrases, vec![("well, well".to_string(), CEFRLevel::B1)]);
+    }
+
+    #[test]
+    fn malformed_input_is_reported() {
+        let mut builder = DictionaryBuilder::new();
+        let err = builder.add_csv(LevelSource::Core, "x.csv", "word,pos\ngo,verb\n").err().unwrap();
+        assert!(matches!(err, DictionaryError::MissingColumn { column: "level", .. }));
+
+        let err = builder.add_csv(LevelSource::Core, "x.csv", "word,level\n\"go,A1\n").err().unwrap();
+        assert!(matches!(err, DictionaryError::Malformed { line: 2, .. }));
+    }
+
+    #[test]
+    fn abstract_flag_falls_back_to_noun_suffixes() {
+        let dict = build("word,pos,level\ninformation,noun,A2\nstation,verb,B2\n");
+        assert!(entries_for(&dict, "information")[0].is_abstract);
+        assert!(!entries_for(&dict, "station")[0].is_abstract);
+    }
+
+    #[test]
+    fn profile_pos_names_are_normalized() {
+        assert_eq!(normalize_pos("Noun"), "noun");
+        assert_eq!(normalize_pos("be-verb"), "verb");
+        assert_eq!(normalize_pos("preposition"), "prep");
+        assert_eq!(normalize_pos("modal auxiliary"), "modal");
+        assert_eq!(normalize_pos(""), "");
+    }
+
+    #[test]
+    fn earlier_sources_come_first_for_a_shared_headword() {
+        let mut builder = DictionaryBuilder::new();
+        builder.add_csv(LevelSource::CefrJ, "cefrj.csv", "headword,pos,CEFR
+run,verb,A1
+").unwrap();
+        builder.add_csv(LevelSource::Octanove, "octanove.csv", "headword,pos,CEFR
+run,noun,C1
+").unwrap();
+        let dict = builder.finish();
+        let sources: Vec<LevelSource> = entries_for(&dict, "run").iter().map(|e| e.source).collect();
+        assert_eq!(sources, vec![LevelSource::CefrJ, LevelSource::Octanove]);
+    }
+
+    #[test]
+    fn slash_variants_share_the_headword_entry() {
+        assert_eq!(split_variants("a.m./A.M./am/AM"), vec!["a.m.", "A.M.", "am", "AM"]);
+        assert_eq!(split_variants(" OK / okay "), vec!["OK", "okay"]);
+
+        let dict = build("word,pos,level
+OK/okay,adj,A1
+");
+        for key in ["OK", "ok", "okay"] {
+            let entries = entries_for(&dict, key);
+            assert_eq!(entries.len(), 1, "{}", key);
+            assert_eq!(entries[0].lemma, "OK");
+        }
+    }
+
+    #[test]
+    fn canonical_headwords_rank_before_variant_spellings() {
+        let dict = build("word,pos,level
+a.m./A.M./am/AM,adv,A1
+am,verb,A1
+");
+        let am = entries_for(&dict, "am");
+        assert_eq!(am.iter().map(|e| e.pos.as_str()).collect::<Vec<_>>(), vec!["verb", "adv"]);
+        assert_eq!(entries_for(&dict, "A.M.")[0].lemma, "a.m.");
+    }
+}
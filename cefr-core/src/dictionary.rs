@@ -1,179 +1,267 @@
-use std::collections::HashMap;
 use lazy_static::lazy_static;
-use aho_corasick::AhoCorasick;
+use fst::Map;
+use crate::lemmatizer::lemma_candidates;
+use crate::fst_dict::exchange_lemmas;
+use crate::phrases::PhraseMatcher;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum CEFRLevel {
-    A1, A2, B1, B2, C1, C2, Unknown
-}
-
-impl CEFRLevel {
-    pub fn from_str(s: &str) -> Self {
-        match s.trim().to_uppercase().as_str() {
-            "A1" => CEFRLevel::A1,
-            "A2" => CEFRLevel::A2,
-            "B1" => CEFRLevel::B1,
-            "B2" => CEFRLevel::B2,
-            "C1" => CEFRLevel::C1,
-            "C2" => CEFRLevel::C2,
-            _ => CEFRLevel::Unknown,
-        }
-    }
-}
+pub use crate::dict_source::{CEFRLevel, LevelSource, ObjectType, has_abstract_suffix};
 
 #[derive(Debug, Clone)]
 pub struct WordEntry {
-    pub lemma: String,
-    pub pos: String,
+    pub lemma: &'static str,
+    pub pos: &'static str,
     pub level: CEFRLevel,
     pub is_abstract: bool,
+    pub source: LevelSource,
+    /// Leveled senses from assets/senses.csv, default sense first; empty for
+    /// words listed with a single level
+    pub senses: &'static [Sense],
+}
+
+/// One meaning of a polysemous word, with the cues `senses::select_sense` scores.
+#[derive(Debug)]
+pub struct Sense {
+    pub pos: &'static str,
+    pub gloss: &'static str,
+    pub level: CEFRLevel,
+    pub collocates: &'static [&'static str],
+    pub object: ObjectType,
 }
 
+// Generated by build.rs from the vocabulary CSVs:
+// ENTRIES (laid out key by key), GROUPS (key ordinal -> ENTRIES range),
+// PHRASES, INDEX_FST (key -> key ordinal) and PHRASE_FST (the phrase automaton).
+include!(concat!(env!("OUT_DIR"), "/dictionary_data.rs"));
+
 pub struct Dictionary {
-    pub words: HashMap<String, Vec<WordEntry>>,
-    pub phrases: Vec<(String, CEFRLevel)>,
-    pub phrase_matcher: AhoCorasick,
+    /// Lookup key -> key ordinal, an index into GROUPS
+    index: Map<&'static [u8]>,
+    pub phrases: &'static [(&'static str, CEFRLevel)],
+    pub phrase_matcher: PhraseMatcher,
 }
 
 impl Dictionary {
+    /// The embedded dictionary (both vocabulary profiles, then the inflection
+    /// list in assets/dictionary.csv), precompiled by build.rs.
+    /// Nothing is parsed or copied here: lookups and phrase matching read the
+    /// static tables and automata.
     pub fn new() -> Self {
-        // In a real scenario, we might want to load this from an external file at runtime
-        // or embed a larger file. 
-        let csv_content = include_str!("../assets/dictionary.csv");
-        let mut words = HashMap::new();
-        let mut phrases = Vec::new();
-        let mut patterns = Vec::new();
-
-        for line in csv_content.lines().skip(1) { // Skip header
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() < 3 { continue; } // Relaxed check, abstract might be missing
-
-            let lemma = parts[0].trim().to_string();
-            let pos = parts[1].trim().to_string();
-            let level = CEFRLevel::from_str(parts[2]);
-            let is_abstract = if parts.len() > 3 { parts[3].trim() == "true" } else { false };
-
-            // Logic to handle Phrases
-            if lemma.contains(' ') {
-                phrases.push((lemma.clone(), level.clone()));
-                patterns.push(lemma.clone());
-            }
-
-            let entry = WordEntry {
-                lemma: lemma.clone(),
-                pos,
-                level,
-                is_abstract,
-            };
-
-            // key is original casing from CSV, but usually CSV is lowercased.
-            // We store it as is, lookup handles casing.
-            words.entry(lemma).or_insert_with(Vec::new).push(entry);
-        }
-
-        let phrase_matcher = AhoCorasick::new(&patterns).unwrap();
+        let index = Map::new(INDEX_FST).expect("precompiled dictionary index is corrupt");
+        let phrase_matcher = PhraseMatcher::from_static(PHRASE_FST);
 
         Dictionary {
-            words,
-            phrases,
+            index,
+            phrases: &PHRASES,
             phrase_matcher,
         }
     }
 
-    pub fn lookup_all(&self, word: &str) -> Option<&Vec<WordEntry>> {
+    /// Entries stored under exactly `key` (no case folding or morphology).
+    fn get(&self, key: &str) -> Option<&'static [WordEntry]> {
+        let ordinal = self.index.get(key)?;
+        let &(offset, count) = GROUPS.get(ordinal as usize)?;
+        Some(&ENTRIES[offset as usize..(offset + count) as usize])
+    }
+
+    pub fn lookup_all(&self, word: &str) -> Option<&'static [WordEntry]> {
         // 1. Exact match
-        if let Some(entries) = self.words.get(word) {
+        if let Some(entries) = self.get(word) {
             return Some(entries);
         }
 
         // 2. Case-insensitive
         let lower = word.to_lowercase();
-        if let Some(entries) = self.words.get(&lower) {
+        if let Some(entries) = self.get(&lower) {
             return Some(entries);
         }
 
-        // 3. Simple Morphology Fallback
-        if lower.ends_with('s') {
-            let lemma = &lower[0..lower.len()-1];
-            if let Some(entries) = self.words.get(lemma) {
-                return Some(entries);
-            }
-            if lower.ends_with("es") {
-                let lemma = &lower[0..lower.len()-2];
-                 if let Some(entries) = self.words.get(lemma) {
-                    return Some(entries);
+        // 3. Morphology: ECDICT's inflection index when loaded, then the lemmatizer
+        exchange_lemmas(&lower).into_iter()
+            .chain(lemma_candidates(&lower, None))
+            .find_map(|lemma| self.get(&lemma))
+    }
+
+    pub fn lookup(&self, word: &str, pos_tag: Option<&str>) -> Option<&'static WordEntry> {
+        // 1. Exact match, 2. case-insensitive
+        let lower = word.to_lowercase();
+        let listed = self.get(word).or_else(|| self.get(&lower));
+        if let Some(entries) = listed {
+            match pos_tag {
+                None => return self.select_best_entry(entries, None),
+                Some(tag) => {
+                    if let Some(entry) = self.entry_for_tag(entries, tag) {
+                        return Some(entry);
+                    }
                 }
             }
         }
-        
-        if lower.ends_with("ed") {
-             let lemma = &lower[0..lower.len()-2];
-             if let Some(entries) = self.words.get(lemma) {
-                return Some(entries);
-            }
-        }
 
-        None
+        // 3. Morphology: ECDICT's inflection index when loaded (a large lexicon
+        // beats suffix guessing), then the lemmatizer, which uses the tag so
+        // "left"/VBD -> leave but "left"/JJ stays put
+        let mut lemmas = exchange_lemmas(&lower).into_iter()
+            .chain(lemma_candidates(&lower, pos_tag))
+            .filter_map(|lemma| self.get(&lemma));
+
+        match (listed, pos_tag) {
+            // A listed form whose rows don't fit the tag may still inflect
+            // another lemma that does: the core list has "lives" -> life, but
+            // "lives"/VBZ is live. Otherwise keep the listed fallback.
+            (Some(entries), Some(tag)) => lemmas
+                .find_map(|es| self.entry_for_tag(es, tag))
+                .or_else(|| self.select_best_entry(entries, pos_tag)),
+            _ => lemmas.next().and_then(|entries| self.select_best_entry(entries, pos_tag)),
+        }
     }
 
-    pub fn lookup(&self, word: &str, pos_tag: Option<&str>) -> Option<&WordEntry> {
-        // 1. Exact match
-        if let Some(entries) = self.words.get(word) {
-            return self.select_best_entry(entries, pos_tag);
+    fn select_best_entry(&self, entries: &'static [WordEntry], pos_tag: Option<&str>) -> Option<&'static WordEntry> {
+        if let Some(entry) = pos_tag.and_then(|tag| self.entry_for_tag(entries, tag)) {
+            return Some(entry);
         }
+        // No POS match: the core list's row, else the lowest level, so a rare
+        // reading ("that" adverb, B2) doesn't stand in for a common one
+        entries.iter()
+            .find(|e| e.source == LevelSource::Core)
+            .or_else(|| entries.iter().min_by_key(|e| level_rank(&e.level)))
+    }
 
-        // 2. Case-insensitive
-        let lower = word.to_lowercase();
-        if let Some(entries) = self.words.get(&lower) {
-            return self.select_best_entry(entries, pos_tag);
+    /// The entry whose POS fits `tag`, if any.
+    fn entry_for_tag(&self, entries: &'static [WordEntry], tag: &str) -> Option<&'static WordEntry> {
+        if let Some(entry) = entries.iter().find(|e| tag_fits_pos(tag, e.pos)) {
+            return Some(entry);
         }
 
-        // 3. Simple Morphology Fallback (Naive Lemmatization)
-        // If word ends with 's', try removing it
-        if lower.ends_with('s') {
-            let lemma = &lower[0..lower.len()-1];
-            if let Some(entries) = self.words.get(lemma) {
-                return self.select_best_entry(entries, pos_tag);
-            }
-            // 'es' check? e.g. buses -> bus
-            if lower.ends_with("es") {
-                let lemma = &lower[0..lower.len()-2];
-                 if let Some(entries) = self.words.get(lemma) {
-                    return self.select_best_entry(entries, pos_tag);
-                }
-            }
+        // Inflected forms from the core list carry no POS; retry on their
+        // lemma, which the vocabulary profiles do tag ("books"/VBZ -> "book" verb)
+        entries.iter()
+            .find(|e| e.pos.is_empty())
+            .and_then(|e| self.get(e.lemma))
+            .and_then(|es| es.iter().find(|e| tag_fits_pos(tag, e.pos)))
+    }
+
+    /// A hyphenated compound that `lookup` misses ("well-known", "e-mail"):
+    /// the closed spelling if listed ("email"), else one entry per part
+    /// when every part is known. The last part is the head and gets the tag.
+    pub fn lookup_compound(&self, word: &str, pos_tag: Option<&str>) -> Option<Vec<&'static WordEntry>> {
+        if !word.contains('-') {
+            return None;
         }
-        
-        // 'ed'
-        if lower.ends_with("ed") {
-             let lemma = &lower[0..lower.len()-2];
-             if let Some(entries) = self.words.get(lemma) {
-                return self.select_best_entry(entries, pos_tag);
-            }
-            // 'd' (loved -> love) - naive, might be problematic for 'bed' -> 'be' (false pos)
-            // safer to skip for now or verify dict existence
+        let closed: String = word.chars().filter(|&c| c != '-').collect();
+        if let Some(entry) = self.lookup(&closed, pos_tag) {
+            return Some(vec![entry]);
         }
 
-        None
+        let parts: Vec<&str> = word.split('-').filter(|p| !p.is_empty()).collect();
+        let head = parts.len().checked_sub(1)?;
+        parts.iter()
+            .enumerate()
+            .map(|(i, part)| self.lookup(part, if i == head { pos_tag } else { None }))
+            .collect()
     }
+}
 
-    fn select_best_entry<'a>(&self, entries: &'a Vec<WordEntry>, pos_tag: Option<&str>) -> Option<&'a WordEntry> {
-        if let Some(tag) = pos_tag {
-            let simple_pos = if tag.starts_with("N") { "noun" }
-                            else if tag.starts_with("V") { "verb" }
-                            else if tag.starts_with("J") { "adj" }
-                            else if tag.starts_with("R") { "adv" } 
-                            else { "other" };
-            
-            if let Some(entry) = entries.iter().find(|e| e.pos == simple_pos) {
-                return Some(entry);
-            }
-        }
-        // Fallback to first
-        entries.first()
+/// Map a Penn Treebank tag onto the normalized POS names stored in `WordEntry.pos`.
+pub fn pos_for_tag(tag: &str) -> &'static str {
+    match tag {
+        "DT" | "PDT" | "WDT" => "determiner",
+        "IN" => "prep",
+        "CC" => "conj",
+        "PRP" | "PRP$" | "WP" | "WP$" => "pronoun",
+        "MD" => "modal",
+        "TO" => "to",
+        "CD" => "num",
+        "UH" => "interj",
+        t if t.starts_with('N') => "noun",
+        t if t.starts_with('V') => "verb",
+        t if t.starts_with('J') => "adj",
+        t if t.starts_with('R') => "adv",
+        _ => "other",
+    }
+}
+
+/// Whether a dictionary POS can carry a Penn Treebank tag. IN covers both
+/// prepositions and subordinating conjunctions ("that", "because").
+pub fn tag_fits_pos(tag: &str, pos: &str) -> bool {
+    match tag {
+        "IN" => matches!(pos, "prep" | "conj"),
+        _ => pos == pos_for_tag(tag),
+    }
+}
+
+/// Order of levels for picking the easiest entry; unknown levels sort last
+fn level_rank(level: &CEFRLevel) -> u8 {
+    match level {
+        CEFRLevel::A1 => 1,
+        CEFRLevel::A2 => 2,
+        CEFRLevel::B1 => 3,
+        CEFRLevel::B2 => 4,
+        CEFRLevel::C1 => 5,
+        CEFRLevel::C2 => 6,
+        CEFRLevel::Unknown => u8::MAX,
     }
 }
 
 lazy_static! {
     pub static ref DICT: Dictionary = Dictionary::new();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, tag: &str) -> &'static WordEntry {
+        DICT.lookup(word, Some(tag)).unwrap_or_else(|| panic!("{} not found", word))
+    }
+
+    #[test]
+    fn tag_selects_the_matching_profile_entry() {
+        assert_eq!((entry("book", "NN").pos, &entry("book", "NN").level), ("noun", &CEFRLevel::A1));
+        assert_eq!((entry("book", "VB").pos, &entry("book", "VB").level), ("verb", &CEFRLevel::B1));
+        assert_eq!(entry("exterior", "NN").source, LevelSource::Octanove);
+        assert_eq!(entry("exterior", "NN").level, CEFRLevel::C1);
+    }
+
+    #[test]
+    fn in_matches_conjunctions_as_well_as_prepositions() {
+        let that = entry("that", "IN");
+        assert_eq!(that.pos, "conj");
+        assert_eq!(that.level, CEFRLevel::A1);
+        assert!(tag_fits_pos("IN", "prep"));
+        assert!(!tag_fits_pos("NN", "conj"));
+    }
+
+    #[test]
+    fn unmatched_tags_fall_back_to_the_core_or_easiest_entry() {
+        // No interjection reading: not the first row, "that" adverb B2
+        assert_eq!(entry("that", "UH").level, CEFRLevel::A1);
+        assert_eq!(entry("that", "RB").level, CEFRLevel::B2);
+    }
+
+    #[test]
+    fn inflections_reach_their_lemma() {
+        assert_eq!(entry("books", "VBZ").pos, "verb");
+        assert_eq!(entry("books", "NNS").pos, "noun");
+    }
+
+    #[test]
+    fn listed_plurals_fall_through_to_verb_lemmas() {
+        // The core list files "lives"/"leaves" under life/leaf
+        assert_eq!((entry("lives", "NNS").lemma, entry("lives", "NNS").pos), ("life", "noun"));
+        assert_eq!((entry("lives", "VBZ").lemma, entry("lives", "VBZ").pos), ("live", "verb"));
+        assert_eq!((entry("leaves", "VBZ").lemma, entry("leaves", "VBZ").pos), ("leave", "verb"));
+        assert_eq!(entry("moves", "VBZ").lemma, "move");
+    }
+
+    #[test]
+    fn lookup_all_reads_the_static_tables_with_fallbacks() {
+        let key = |entries: &[WordEntry]| entries.iter().map(|e| (e.lemma, e.pos)).collect::<Vec<_>>();
+        let book = DICT.lookup_all("book").unwrap();
+        // A slice of ENTRIES rather than a copy of it
+        assert!(ENTRIES.as_ptr_range().contains(&book.as_ptr()));
+        assert!(key(book).contains(&("book", "noun")) && key(book).contains(&("book", "verb")));
+        // Case folding, then morphology
+        assert_eq!(key(DICT.lookup_all("Book").unwrap()), key(book));
+        assert!(DICT.lookup_all("booked").is_some());
+        assert!(DICT.lookup_all("qzxv").is_none());
+    }
+}
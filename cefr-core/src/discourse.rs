@@ -2,7 +2,7 @@ use serde::Serialize;
 use std::collections::HashSet;
 use lazy_static::lazy_static;
 use phf::phf_set;
-use crate::dictionary::{DICT, CEFRLevel};
+use crate::dictionary::{DICT, CEFRLevel, has_abstract_suffix};
 use crate::pos::TaggedToken;
 
 #[derive(Serialize, Default, Debug)]
@@ -26,7 +26,7 @@ pub fn is_common_name(word: &str) -> bool {
 lazy_static! {
     static ref TITLES: HashSet<&'static str> = {
         let mut s = HashSet::new();
-        // Tokens are stripped of punctuation by default tokenization, so "Mr." -> "Mr"
+        // The abbreviation period is a separate token, so "Mr." -> "Mr"
         for t in &["Mr", "Mrs", "Ms", "Dr", "Prof", "Capt", "Gen", "Sen", "Rep", "St", "Mt"] {
             s.insert(*t);
         }
@@ -42,7 +42,9 @@ impl DiscourseAnalyzer {
         let mut entity_count = 0.0;
 
         for (_sent_idx, sent) in sentences.iter().enumerate() {
-            for (word_idx, token) in sent.iter().enumerate() {
+            // Words only; the title check below looks past the period in "Mr. Brown"
+            let words: Vec<&TaggedToken> = sent.iter().filter(|t| t.is_word()).collect();
+            for (word_idx, token) in words.iter().enumerate() {
                 total_words += 1.0;
                 let word = &token.word;
                 let lower_word = word.to_lowercase();
@@ -60,7 +62,7 @@ impl DiscourseAnalyzer {
                      }
                 } else {
                      // Check suffixes for abstractness if not in dict
-                     if word.ends_with("tion") || word.ends_with("ment") || word.ends_with("ness") || word.ends_with("ity") || word.ends_with("ism") {
+                     if has_abstract_suffix(word) {
                          abstract_nouns += 1.0;
                      }
                 }
@@ -100,7 +102,7 @@ impl DiscourseAnalyzer {
                             
                             // Title trigger: "Mr. Brown"
                             if word_idx > 0 {
-                                let prev = &sent[word_idx - 1].word;
+                                let prev = &words[word_idx - 1].word;
                                 // Simple check on previous word
                                 if TITLES.contains(prev.as_str()) {
                                     entity_count += 1.0;
@@ -0,0 +1,130 @@
+//! Parser for the ECDICT `exchange` field, e.g. `p:went/d:gone/i:going/3:goes/0:go`.
+//!
+//! Shared by the library (runtime lookups) and src/bin/build_dict.rs, which
+//! derives the inflection -> lemma index from it.
+#![allow(dead_code)]
+
+use serde::Serialize;
+
+/// One inflectional slot of the exchange field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InflectionKind {
+    Past,              // p
+    PastParticiple,    // d
+    PresentParticiple, // i
+    ThirdPerson,       // 3
+    Comparative,       // r
+    Superlative,       // t
+    Plural,            // s
+}
+
+impl InflectionKind {
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'p' => Some(InflectionKind::Past),
+            'd' => Some(InflectionKind::PastParticiple),
+            'i' => Some(InflectionKind::PresentParticiple),
+            '3' => Some(InflectionKind::ThirdPerson),
+            'r' => Some(InflectionKind::Comparative),
+            't' => Some(InflectionKind::Superlative),
+            's' => Some(InflectionKind::Plural),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> char {
+        match self {
+            InflectionKind::Past => 'p',
+            InflectionKind::PastParticiple => 'd',
+            InflectionKind::PresentParticiple => 'i',
+            InflectionKind::ThirdPerson => '3',
+            InflectionKind::Comparative => 'r',
+            InflectionKind::Superlative => 't',
+            InflectionKind::Plural => 's',
+        }
+    }
+}
+
+/// A parsed exchange field.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Exchange {
+    /// `0:` the lemma this word is an inflection of
+    pub lemma: Option<String>,
+    /// `1:` which inflections of `lemma` this word is (`1:pd` = past and past participle)
+    pub lemma_kinds: Vec<InflectionKind>,
+    /// The word's own inflected forms, in field order
+    pub forms: Vec<(InflectionKind, String)>,
+}
+
+impl Exchange {
+    /// Re-serialize only the word's own forms (`p:went/d:gone`), dropping `0:`/`1:`.
+    pub fn forms_field(&self) -> String {
+        self.forms
+            .iter()
+            .map(|(kind, form)| format!("{}:{}", kind.code(), form))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Parse an exchange field. Unknown slots and malformed items are skipped.
+pub fn parse_exchange(field: &str) -> Exchange {
+    let mut exchange = Exchange::default();
+
+    for item in field.split('/') {
+        let Some((code, value)) = item.split_once(':') else { continue };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        let mut code_chars = code.trim().chars();
+        let (Some(code), None) = (code_chars.next(), code_chars.next()) else { continue };
+        match code {
+            '0' => exchange.lemma = Some(value.to_string()),
+            '1' => exchange.lemma_kinds = value.chars().filter_map(InflectionKind::from_code).collect(),
+            _ => {
+                if let Some(kind) = InflectionKind::from_code(code) {
+                    exchange.forms.push((kind, value.to_string()));
+                }
+            }
+        }
+    }
+
+    exchange
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lemma_kinds_and_forms() {
+        let verb = parse_exchange("p:went/d:gone/i:going/3:goes");
+        assert_eq!(verb.lemma, None);
+        assert_eq!(verb.forms[0], (InflectionKind::Past, "went".to_string()));
+        assert_eq!(verb.forms.len(), 4);
+
+        let form = parse_exchange("0:go/1:p");
+        assert_eq!(form.lemma.as_deref(), Some("go"));
+        assert_eq!(form.lemma_kinds, vec![InflectionKind::Past]);
+        assert!(form.forms.is_empty());
+    }
+
+    #[test]
+    fn skips_unknown_and_malformed_items() {
+        let parsed = parse_exchange("s:boxes/x:what/p:/garbage/ab:c/r: bigger ");
+        assert_eq!(parsed.forms, vec![
+            (InflectionKind::Plural, "boxes".to_string()),
+            (InflectionKind::Comparative, "bigger".to_string()),
+        ]);
+        assert_eq!(parse_exchange(""), Exchange::default());
+    }
+
+    #[test]
+    fn forms_field_drops_the_lemma_slots() {
+        let parsed = parse_exchange("0:go/1:p/p:went/d:gone");
+        assert_eq!(parsed.forms_field(), "p:went/d:gone");
+    }
+}
@@ -2,9 +2,43 @@ use fst::Map;
 use wasm_bindgen::prelude::*;
 use std::sync::Mutex;
 use lazy_static::lazy_static;
+use byteorder::{ByteOrder, LittleEndian};
+use crate::dictionary::DICT;
+use crate::exchange::{parse_exchange, InflectionKind};
 
 lazy_static! {
     static ref FST_INDEX: Mutex<Option<Map<Vec<u8>>>> = Mutex::new(None);
+    static ref EXCHANGE_INDEX: Mutex<Option<ExchangeIndex>> = Mutex::new(None);
+}
+
+/// build_dict 生成的词形索引: exchange.fst (单词 -> 偏移量) + exchange.data
+struct ExchangeIndex {
+    map: Map<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+impl ExchangeIndex {
+    /// 读取单词的记录: (所属原形列表, 自身的词形变化字段)
+    fn record(&self, word: &str) -> Option<(Vec<String>, String)> {
+        let offset = self.map.get(word.to_lowercase())? as usize;
+        let len = LittleEndian::read_u32(self.data.get(offset..offset + 4)?) as usize;
+        let bytes = self.data.get(offset + 4..offset + 4 + len)?;
+        serde_json::from_slice(bytes).ok()
+    }
+
+    /// 单词 (或其原形) 的词形变化; 变形词本身没有, 改查原形
+    fn inflections(&self, word: &str) -> Vec<(InflectionKind, String)> {
+        let Some((lemmas, own_forms)) = self.record(word) else { return Vec::new() };
+        let field = if own_forms.is_empty() {
+            match lemmas.first().and_then(|lemma| self.record(lemma)) {
+                Some((_, forms)) => forms,
+                None => return Vec::new(),
+            }
+        } else {
+            own_forms
+        };
+        parse_exchange(&field).forms
+    }
 }
 
 // 确保错误类型可转换为 JsValue
@@ -41,3 +75,103 @@ pub fn lookup_fst_offset(word: &str) -> Option<u64> {
     }
     None
 }
+
+#[wasm_bindgen]
+pub fn load_exchange_index(fst_data: &[u8], data: &[u8]) -> Result<(), JsValue> {
+    let map = Map::new(fst_data.to_vec()).map_err(|e| FstError(format!("加载词形索引失败: {}", e)))?;
+
+    let mut global = EXCHANGE_INDEX.lock().map_err(|_| FstError("Mutex 中毒".to_string()))?;
+    *global = Some(ExchangeIndex { map, data: data.to_vec() });
+
+    Ok(())
+}
+
+/// ECDICT 记录的原形 (went -> go)。索引未加载或不是变形词时为空。
+pub fn exchange_lemmas(word: &str) -> Vec<String> {
+    let Ok(lock) = EXCHANGE_INDEX.lock() else { return Vec::new() };
+    lock.as_ref()
+        .and_then(|index| index.record(word))
+        .map(|(lemmas, _)| lemmas)
+        .unwrap_or_default()
+}
+
+/// 单词的原形, 优先返回 CEFR 词表中收录的那个
+#[wasm_bindgen]
+pub fn lemma_of(word: &str) -> Option<String> {
+    let lemmas = exchange_lemmas(word);
+    lemmas.iter()
+        .find(|l| DICT.lookup_all(l).is_some())
+        .or_else(|| lemmas.first())
+        .cloned()
+}
+
+#[derive(serde::Serialize)]
+struct Inflection {
+    kind: InflectionKind,
+    form: String,
+}
+
+/// 单词 (或其原形) 的全部词形变化: [{ kind: "past", form: "went" }, ...]
+#[wasm_bindgen]
+pub fn inflections_of(word: &str) -> Result<JsValue, JsValue> {
+    let forms = EXCHANGE_INDEX.lock()
+        .map_err(|_| FstError("Mutex 中毒".to_string()))?
+        .as_ref()
+        .map(|index| index.inflections(word))
+        .unwrap_or_default();
+
+    let inflections: Vec<Inflection> = forms.into_iter()
+        .map(|(kind, form)| Inflection { kind, form })
+        .collect();
+    serde_wasm_bindgen::to_value(&inflections)
+        .map_err(|e| FstError(format!("序列化词形变化失败: {}", e)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fst::MapBuilder;
+
+    /// 按 build_dict 的格式构造索引 (键须已排序): 每条记录 = u32 长度 + JSON [原形列表, 词形变化字段]
+    fn index(records: &[(&str, &[&str], &str)]) -> ExchangeIndex {
+        let mut builder = MapBuilder::memory();
+        let mut data = Vec::new();
+        for (word, lemmas, forms) in records {
+            let bytes = serde_json::to_vec(&(lemmas, forms)).unwrap();
+            builder.insert(word, data.len() as u64).unwrap();
+            data.extend((bytes.len() as u32).to_le_bytes());
+            data.extend(bytes);
+        }
+        let map = Map::new(builder.into_inner().unwrap()).unwrap();
+        ExchangeIndex { map, data }
+    }
+
+    #[test]
+    fn records_resolve_inflections_to_lemmas() {
+        let index = index(&[
+            ("go", &[], "p:went/d:gone/i:going/3:goes"),
+            ("went", &["go"], ""),
+        ]);
+        assert_eq!(index.record("Went"), Some((vec!["go".to_string()], String::new())));
+        assert_eq!(index.record("walked"), None);
+    }
+
+    #[test]
+    fn inflected_forms_report_their_lemmas_inflections() {
+        let index = index(&[
+            ("go", &[], "p:went/d:gone/i:going/3:goes"),
+            ("orphan", &["missing"], ""),
+            ("went", &["go"], ""),
+        ]);
+        let expected = vec![
+            (InflectionKind::Past, "went".to_string()),
+            (InflectionKind::PastParticiple, "gone".to_string()),
+            (InflectionKind::PresentParticiple, "going".to_string()),
+            (InflectionKind::ThirdPerson, "goes".to_string()),
+        ];
+        assert_eq!(index.inflections("go"), expected);
+        assert_eq!(index.inflections("went"), expected);
+        assert!(index.inflections("orphan").is_empty());
+        assert!(index.inflections("unknown").is_empty());
+    }
+}
@@ -0,0 +1,557 @@
+//! Grammar structures in the manner of the English Grammar Profile: each
+//! detected construction carries the CEFR level at which learners typically
+//! master it, so a text built on third conditionals and inversion rates
+//! above simple prose with the same vocabulary.
+//!
+//! Detectors work on the tagged sentence, using lowercased words for
+//! auxiliaries (verb clitics already hold their expansion: "'d" -> "had").
+//! Tenses and passives are read off `chunk_verb_groups`, the same groups
+//! the tense distribution counts.
+
+use std::ops::Range;
+use serde::Serialize;
+use crate::dictionary::CEFRLevel;
+use crate::lemmatizer::lemmatize;
+use crate::offsets::Offsets;
+use crate::pos::TaggedToken;
+use crate::verb_groups::{chunk_verb_groups, is_auxiliary, is_be, lower, Aspect, Tense, VerbGroup, Voice};
+use crate::{level_to_score, score_to_level};
+
+/// Examples reported per structure
+const MAX_EXAMPLES: usize = 3;
+/// The grammar score averages this many of the highest-level structures found
+const TOP_STRUCTURES: usize = 3;
+
+#[derive(Serialize, Default, Debug)]
+pub struct GrammarMetrics {
+    /// Level of the hardest structures used; `None` when none was found
+    pub level: Option<String>,
+    pub score: f64,
+    /// Structures found at least once, in `STRUCTURES` order
+    pub structures: Vec<StructureCount>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StructureCount {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub level: String,
+    pub count: usize,
+    pub examples: Vec<StructureExample>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StructureExample {
+    /// Index into `AnalysisResult.sentences`
+    pub sentence: usize,
+    /// The matched words
+    pub offsets: Offsets,
+}
+
+/// Finds a structure in one sentence; returns the token ranges it covers.
+type Detector = fn(&[TaggedToken]) -> Vec<Range<usize>>;
+
+struct Structure {
+    id: &'static str,
+    name: &'static str,
+    level: CEFRLevel,
+    detect: Detector,
+}
+
+const STRUCTURES: &[Structure] = &[
+    Structure { id: "present_perfect", name: "Present perfect", level: CEFRLevel::A2, detect: present_perfect },
+    Structure { id: "present_perfect_continuous", name: "Present perfect continuous", level: CEFRLevel::B1, detect: present_perfect_continuous },
+    Structure { id: "past_perfect", name: "Past perfect", level: CEFRLevel::B1, detect: past_perfect },
+    Structure { id: "past_perfect_continuous", name: "Past perfect continuous", level: CEFRLevel::B2, detect: past_perfect_continuous },
+    Structure { id: "used_to", name: "\"used to\" for past habits", level: CEFRLevel::A2, detect: used_to },
+    Structure { id: "zero_conditional", name: "Zero conditional", level: CEFRLevel::A2, detect: zero_conditional },
+    Structure { id: "first_conditional", name: "First conditional", level: CEFRLevel::B1, detect: first_conditional },
+    Structure { id: "second_conditional", name: "Second conditional", level: CEFRLevel::B1, detect: second_conditional },
+    Structure { id: "third_conditional", name: "Third conditional", level: CEFRLevel::B2, detect: third_conditional },
+    Structure { id: "reported_speech", name: "Reported speech", level: CEFRLevel::B1, detect: reported_speech },
+    Structure { id: "passive_modal", name: "Passive with a modal", level: CEFRLevel::B1, detect: passive_modal },
+    Structure { id: "non_defining_relative", name: "Non-defining relative clause", level: CEFRLevel::B1, detect: non_defining_relative },
+    Structure { id: "cleft_sentence", name: "Cleft sentence", level: CEFRLevel::B2, detect: cleft_sentence },
+    Structure { id: "negative_inversion", name: "Negative inversion", level: CEFRLevel::C1, detect: negative_inversion },
+];
+
+pub struct GrammarAnalyzer;
+
+impl GrammarAnalyzer {
+    /// `to_offsets` maps a byte range of the tokenized text into the result's offsets.
+    pub fn analyze(sentences: &[Vec<TaggedToken>], to_offsets: impl Fn(Range<usize>) -> Offsets) -> GrammarMetrics {
+        let mut structures = Vec::new();
+        for structure in STRUCTURES {
+            let mut count = 0;
+            let mut examples = Vec::new();
+            for (s, sent) in sentences.iter().enumerate() {
+                for range in (structure.detect)(sent) {
+                    count += 1;
+                    if examples.len() < MAX_EXAMPLES {
+                        let span = sent[range.start].span.start..sent[range.end - 1].span.end;
+                        examples.push(StructureExample { sentence: s, offsets: to_offsets(span) });
+                    }
+                }
+            }
+            if count > 0 {
+                structures.push(StructureCount {
+                    id: structure.id,
+                    name: structure.name,
+                    level: format!("{:?}", structure.level),
+                    count,
+                    examples,
+                });
+            }
+        }
+
+        let mut scores: Vec<f64> = STRUCTURES
+            .iter()
+            .filter(|s| structures.iter().any(|found| found.id == s.id))
+            .map(|s| level_to_score(&s.level))
+            .collect();
+        scores.sort_by(|a, b| b.total_cmp(a));
+        scores.truncate(TOP_STRUCTURES);
+        let score = if scores.is_empty() { 0.0 } else { scores.iter().sum::<f64>() / scores.len() as f64 };
+
+        GrammarMetrics {
+            level: (!scores.is_empty()).then(|| score_to_level(score)),
+            score,
+            structures,
+        }
+    }
+}
+
+// ==================== TOKEN HELPERS ====================
+
+/// Could head or open a subject noun phrase ("I", "the", "John", "there")
+fn is_subject_start(tag: &str) -> bool {
+    matches!(tag, "PRP" | "DT" | "EX" | "PRP$" | "CD") || tag.starts_with("NN")
+}
+
+fn is_clause_break(token: &TaggedToken) -> bool {
+    !token.is_word() && matches!(token.word.as_str(), "," | ";" | ":" | "." | "!" | "?" | "--")
+}
+
+/// First index at or after `i` that isn't an adverb (including "not"/"n't").
+fn skip_adverbs(sent: &[TaggedToken], mut i: usize) -> usize {
+    while i < sent.len() && sent[i].tag.starts_with("RB") {
+        i += 1;
+    }
+    i
+}
+
+// ==================== TENSES ====================
+
+/// Spans of the verb groups `keep` accepts
+fn groups_where(sent: &[TaggedToken], keep: impl Fn(&VerbGroup) -> bool) -> Vec<Range<usize>> {
+    chunk_verb_groups(sent).into_iter().filter(|g| keep(g)).map(|g| g.span).collect()
+}
+
+/// Perfect groups without a modal ("would have gone" is no past perfect)
+fn perfect(sent: &[TaggedToken], tense: Tense, aspect: Aspect) -> Vec<Range<usize>> {
+    groups_where(sent, |g| g.modal.is_none() && g.tense == tense && g.aspect == aspect)
+}
+
+fn present_perfect(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    perfect(sent, Tense::Present, Aspect::Perfect)
+}
+
+fn present_perfect_continuous(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    perfect(sent, Tense::Present, Aspect::PerfectProgressive)
+}
+
+fn past_perfect(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    perfect(sent, Tense::Past, Aspect::Perfect)
+}
+
+fn past_perfect_continuous(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    perfect(sent, Tense::Past, Aspect::PerfectProgressive)
+}
+
+/// "used to" / "didn't use to" + base verb, but not "be/get used to" + noun or "-ing"
+fn used_to(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    (0..sent.len().saturating_sub(2))
+        .filter(|&i| {
+            let word = lower(&sent[i]);
+            let negated = i > 0 && matches!(sent[i - 1].tag.as_str(), "RB" | "VBD");
+            (word == "used" || (word == "use" && negated))
+                && lower(&sent[i + 1]) == "to"
+                && sent[skip_adverbs(sent, i + 2)..].first().is_some_and(|t| t.tag == "VB")
+                && !(i > 0 && (is_be(&lower(&sent[i - 1])) || lemmatize(&sent[i - 1].word, Some(&sent[i - 1].tag)) == "get"))
+        })
+        .map(|i| i..skip_adverbs(sent, i + 2) + 1)
+        .collect()
+}
+
+// ==================== CONDITIONALS ====================
+
+/// The verb forms of one clause that tell conditionals apart.
+#[derive(Default)]
+struct ClauseVerbs {
+    modal: Option<String>,
+    /// modal + have + participle ("would have gone")
+    modal_perfect: bool,
+    past_perfect: bool,
+    past: bool,
+    present: bool,
+}
+
+fn clause_verbs(sent: &[TaggedToken], range: Range<usize>) -> ClauseVerbs {
+    let mut verbs = ClauseVerbs::default();
+    for group in chunk_verb_groups(sent).iter().filter(|g| range.contains(&g.span.start)) {
+        let perfect = matches!(group.aspect, Aspect::Perfect | Aspect::PerfectProgressive);
+        match (&group.modal, group.tense) {
+            (Some(modal), _) => {
+                verbs.modal.get_or_insert_with(|| modal.clone());
+                verbs.modal_perfect |= perfect;
+            }
+            (None, Tense::Past) if perfect => verbs.past_perfect = true,
+            (None, Tense::Past) => verbs.past = true,
+            (None, _) => verbs.present = true,
+        }
+    }
+    // Base form straight after its subject is a mistagged present ("if you press")
+    verbs.present |= range.clone().skip(1).any(|i| sent[i].tag == "VB" && matches!(sent[i - 1].tag.as_str(), "PRP" | "NN" | "NNS" | "NNP"));
+    verbs
+}
+
+/// Verbs after which "if" means "whether" ("ask (him) if", "know if").
+const WHETHER_VERBS: &[&str] = &[
+    "ask", "wonder", "know", "see", "check", "decide", "doubt", "find", "remember", "tell",
+    "understand", "care", "mind", "learn", "discover", "guess", "show", "question", "enquire", "inquire",
+];
+
+/// Whether the "if" at `k` introduces a question ("ask him if") or a manner
+/// clause ("as if") rather than a condition.
+fn if_is_not_conditional(sent: &[TaggedToken], k: usize) -> bool {
+    let mut v = k;
+    if v > 0 && sent[v - 1].tag == "PRP" {
+        v -= 1;
+    }
+    let asks = v > 0
+        && sent[v - 1].tag.starts_with("VB")
+        && WHETHER_VERBS.contains(&lemmatize(&sent[v - 1].word, Some(&sent[v - 1].tag)).as_str());
+    asks || k > 0 && lower(&sent[k - 1]) == "as"
+}
+
+/// A fronted condition without "if": "Had I known", "Were I rich",
+/// "Should you need help", each followed by its subject and a comma.
+fn inverted_condition(sent: &[TaggedToken]) -> Option<usize> {
+    let first = sent.iter().position(|t| t.is_word())?;
+    let subject = sent.get(first + 1).filter(|t| t.is_word())?;
+    (matches!(lower(&sent[first]).as_str(), "had" | "were" | "should")
+        && is_subject_start(&subject.tag)
+        && sent[first + 2..].iter().any(|t| t.word == ","))
+    .then_some(first)
+}
+
+/// The if-clause and main clause of a conditional. The if-clause runs to the
+/// next comma (or "then"); the main clause is what precedes "if" or, for a
+/// fronted if-clause, what follows that comma. An inverted auxiliary stands
+/// in for a fronted "if".
+fn conditional_clauses(sent: &[TaggedToken]) -> Option<(Range<usize>, Range<usize>)> {
+    let k = match sent.iter().position(|t| t.is_word() && lower(t) == "if") {
+        Some(k) if if_is_not_conditional(sent, k) => return None,
+        Some(k) => k,
+        None => inverted_condition(sent)?,
+    };
+    let end = sent.iter().position(|t| !t.is_word() && matches!(t.word.as_str(), "." | "!" | "?")).unwrap_or(sent.len());
+    let if_end = (k + 1..end)
+        .find(|&i| is_clause_break(&sent[i]) || lower(&sent[i]) == "then")
+        .unwrap_or(end);
+    let fronted = sent[..k].iter().all(|t| !t.is_word() || t.tag == "CC" || lower(t) == "even" || lower(t) == "only");
+    let main = if fronted { (if_end + 1).min(end)..end } else { 0..k };
+    (main.start < main.end && sent[main.clone()].iter().any(|t| t.tag.starts_with("VB") || t.tag == "MD"))
+        .then_some((k..if_end, main))
+}
+
+/// Which conditional the sentence is: 0-3, or `None`
+fn conditional_type(sent: &[TaggedToken]) -> Option<(u8, Range<usize>)> {
+    let (if_clause, main) = conditional_clauses(sent)?;
+    let mut condition = clause_verbs(sent, if_clause.clone());
+    // "Should you need help, ..." is a first-conditional if-clause
+    if lower(&sent[if_clause.start]) == "should" {
+        condition.modal = None;
+        condition.present = true;
+    }
+    let result = clause_verbs(sent, main.clone());
+    let span = if_clause.start.min(main.start)..if_clause.end.max(main.end);
+    let hypothetical = matches!(result.modal.as_deref(), Some("would" | "could" | "might"));
+
+    let kind = if condition.past_perfect && hypothetical && result.modal_perfect {
+        3
+    } else if (condition.past || condition.past_perfect) && hypothetical && !result.modal_perfect {
+        2
+    } else if condition.present && condition.modal.is_none() {
+        match result.modal.as_deref() {
+            Some("will" | "shall" | "can" | "may" | "might") => 1,
+            None if result.present => 0,
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    Some((kind, span))
+}
+
+fn conditional(sent: &[TaggedToken], kind: u8) -> Vec<Range<usize>> {
+    conditional_type(sent).filter(|(k, _)| *k == kind).map(|(_, span)| span).into_iter().collect()
+}
+
+fn zero_conditional(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    conditional(sent, 0)
+}
+
+fn first_conditional(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    conditional(sent, 1)
+}
+
+fn second_conditional(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    conditional(sent, 2)
+}
+
+fn third_conditional(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    conditional(sent, 3)
+}
+
+// ==================== CLAUSES ====================
+
+const REPORTING_VERBS: &[&str] = &[
+    "say", "tell", "ask", "explain", "report", "claim", "admit", "deny", "promise", "reply",
+    "answer", "mention", "announce", "warn", "insist", "complain", "add", "suggest", "agree",
+];
+
+/// A past reporting verb followed by an optional hearer and then "that",
+/// "if"/"whether", a wh-word, "to" + verb or a backshifted clause:
+/// "she said (that) she was tired", "he asked me where I lived", "told us to wait".
+fn reported_speech(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    let mut found = Vec::new();
+    for i in 0..sent.len() {
+        if !matches!(sent[i].tag.as_str(), "VBD" | "VBN")
+            || !REPORTING_VERBS.contains(&lemmatize(&sent[i].word, Some(&sent[i].tag)).as_str())
+        {
+            continue;
+        }
+        // Hearer: "me", "the manager", "John"; what follows may instead be
+        // the reported clause's subject ("said he was")
+        let mut j = i + 1;
+        if j < sent.len() && matches!(sent[j].tag.as_str(), "PRP" | "NNP") {
+            j += 1;
+        } else if j + 1 < sent.len() && sent[j].tag == "DT" && sent[j + 1].tag.starts_with("NN") {
+            j += 2;
+        }
+        if let Some(j) = [j, i + 1].into_iter().find(|&j| reports_from(sent, j)) {
+            let end = (j..sent.len()).find(|&k| is_clause_break(&sent[k])).unwrap_or(sent.len());
+            found.push(i..end);
+        }
+    }
+    found
+}
+
+/// Whether a reported clause or question starts at `j`.
+fn reports_from(sent: &[TaggedToken], j: usize) -> bool {
+    let Some(next) = sent.get(j).filter(|t| t.is_word()) else { return false };
+    match lower(next).as_str() {
+        "that" | "if" | "whether" => true,
+        "to" => sent.get(j + 1).is_some_and(|t| t.tag == "VB"),
+        _ if matches!(next.tag.as_str(), "WRB" | "WP") => true,
+        // Zero "that": a subject, then a past or modal verb within a few words
+        _ if is_subject_start(&next.tag) => sent[j + 1..].iter().take(4).any(|t| matches!(t.tag.as_str(), "VBD" | "MD")),
+        _ => false,
+    }
+}
+
+/// Modal + be + participle ("can be done") or modal + have been + participle
+/// ("should have been told")
+fn passive_modal(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    groups_where(sent, |g| g.modal.is_some() && g.voice == Voice::Passive)
+}
+
+/// ", who/which/whom/whose ..." or ", in which ...", up to the next break
+fn non_defining_relative(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    if sent.last().is_some_and(|t| t.word == "?") {
+        return Vec::new();
+    }
+    let is_relative = |t: &TaggedToken| {
+        matches!(t.tag.as_str(), "WP" | "WDT" | "WP$") && matches!(lower(t).as_str(), "who" | "which" | "whom" | "whose")
+    };
+    (1..sent.len())
+        .filter(|&i| sent[i - 1].word == ",")
+        .filter_map(|i| {
+            let start = if sent[i].tag == "IN" && sent.get(i + 1).is_some_and(is_relative) { i + 1 } else { i };
+            is_relative(&sent[start]).then(|| {
+                let end = (start + 1..sent.len()).find(|&k| is_clause_break(&sent[k])).unwrap_or(sent.len());
+                i..end
+            })
+        })
+        .collect()
+}
+
+/// It-clefts ("It was John who called", "It is in May that ...") and
+/// wh-clefts ("What I need is a holiday"). "It is important that" is
+/// extraposition, not a cleft, so the focus can't start with an adjective
+/// or participle, nor with "a"/"an".
+fn cleft_sentence(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    let words: Vec<usize> = (0..sent.len()).filter(|&i| sent[i].is_word()).collect();
+    let mut found = Vec::new();
+
+    for (w, &i) in words.iter().enumerate() {
+        if lower(&sent[i]) != "it" || !words.get(w + 1).is_some_and(|&b| is_be(&lower(&sent[b]))) {
+            continue;
+        }
+        let focus = words[w + 2..].iter().copied().skip_while(|&f| matches!(lower(&sent[f]).as_str(), "not" | "only" | "precisely" | "really"));
+        let mut focus = focus.peekable();
+        let Some(&first) = focus.peek() else { continue };
+        // "It is a book that ..." usually just describes "it"
+        if !(is_subject_start(&sent[first].tag) || sent[first].tag == "IN")
+            || sent[first].tag == "EX"
+            || matches!(lower(&sent[first]).as_str(), "a" | "an")
+        {
+            continue;
+        }
+        // The focus phrase is a noun or prepositional phrase ending at "that"/"who"
+        let end = focus.take(8).find(|&f| {
+            let tag = sent[f].tag.as_str();
+            matches!(lower(&sent[f]).as_str(), "that" | "who") || !(is_subject_start(tag) || tag.starts_with("JJ") || tag == "IN" || tag == "POS")
+        });
+        if let Some(end) = end.filter(|&e| matches!(lower(&sent[e]).as_str(), "that" | "who") && e > first) {
+            found.push(i..end + 1);
+        }
+    }
+
+    // "What I need is ...": "what", a subject and its verb, then a be-verb
+    // and a focus that isn't an adjective ("What she said was true")
+    if let (Some(&first), false) = (words.first(), sent.last().is_some_and(|t| t.word == "?")) {
+        if lower(&sent[first]) == "what" && words.get(1).is_some_and(|&s| is_subject_start(&sent[s].tag)) {
+            let be = words[2..].iter().copied().find(|&b| is_be(&lower(&sent[b])) && matches!(sent[b].tag.as_str(), "VBZ" | "VBD"));
+            let verb_before = be.is_some_and(|b| words[2..].iter().take_while(|&&v| v < b).any(|&v| sent[v].tag.starts_with("VB")));
+            let focus_is_adjective = be.and_then(|b| words.iter().find(|&&f| f > b)).is_some_and(|&f| sent[f].tag.starts_with("JJ"));
+            if let Some(b) = be.filter(|_| verb_before && !focus_is_adjective) {
+                found.push(first..b + 1);
+            }
+        }
+    }
+    found
+}
+
+/// Negative or restrictive opener followed by auxiliary-subject order:
+/// "Never have I seen", "Not only did she ...", "Only then did we ...".
+const INVERSION_TRIGGERS: &[&[&str]] = &[
+    &["never"], &["rarely"], &["seldom"], &["hardly"], &["scarcely"], &["barely"], &["little"],
+    &["nowhere"], &["no", "sooner"], &["not", "only"], &["not", "until"], &["only", "when"],
+    &["only", "then"], &["only", "after"], &["only", "if"], &["only", "by"], &["only", "once"],
+    &["under", "no", "circumstances"], &["on", "no", "account"], &["at", "no", "time"], &["in", "no", "way"],
+];
+
+fn negative_inversion(sent: &[TaggedToken]) -> Vec<Range<usize>> {
+    let words: Vec<usize> = (0..sent.len()).filter(|&i| sent[i].is_word()).collect();
+    let lowered: Vec<String> = words.iter().map(|&i| lower(&sent[i])).collect();
+    let Some(trigger) = INVERSION_TRIGGERS
+        .iter()
+        .find(|t| t.len() <= lowered.len() && t.iter().zip(&lowered).all(|(a, b)| a == b))
+    else {
+        return Vec::new();
+    };
+
+    // The first auxiliary immediately followed by its subject; a noun must
+    // then lead on to a verb, or "when I have time" would count
+    (trigger.len()..words.len().saturating_sub(1))
+        .find(|&w| {
+            let (aux, subject) = (&sent[words[w]], &sent[words[w + 1]]);
+            is_auxiliary(&lowered[w], &aux.tag)
+                && is_subject_start(&subject.tag)
+                && (matches!(subject.tag.as_str(), "PRP" | "EX")
+                    || words[w + 2..].iter().take(4).any(|&v| sent[v].tag.starts_with("VB")))
+        })
+        .map(|w| words[0]..words[w + 1] + 1)
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    fn tagged(text: &str) -> Vec<TaggedToken> {
+        tag_sentence(&tokenize_sentence(text), TaggerMode::default())
+    }
+
+    /// Ids of the structures found in `text`.
+    fn found(text: &str) -> Vec<&'static str> {
+        let sent = tagged(text);
+        STRUCTURES.iter().filter(|s| !(s.detect)(&sent).is_empty()).map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn perfect_tenses_and_used_to() {
+        assert_eq!(found("I have lived here for ten years."), ["present_perfect"]);
+        assert_eq!(found("He's been living there since 2010."), ["present_perfect_continuous"]);
+        assert_eq!(found("I'd never seen it before."), ["past_perfect"]);
+        assert_eq!(found("He had been waiting for hours."), ["past_perfect_continuous"]);
+        assert_eq!(found("I didn't use to like coffee."), ["used_to"]);
+        assert!(found("I am used to the noise.").is_empty());
+        assert!(found("He had a car.").is_empty());
+    }
+
+    #[test]
+    fn conditionals_by_type() {
+        assert_eq!(found("If you heat ice, it melts."), ["zero_conditional"]);
+        assert_eq!(found("I will go if you go."), ["first_conditional"]);
+        assert_eq!(found("If I were you, I would go."), ["second_conditional"]);
+        assert!(found("If she had studied, she would have passed.").contains(&"third_conditional"));
+        assert!(found("She'd have come if you had asked.").contains(&"third_conditional"));
+    }
+
+    #[test]
+    fn inverted_conditionals() {
+        assert_eq!(found("Had I known, I would have helped."), ["past_perfect", "third_conditional"]);
+        assert_eq!(found("Were I rich, I would travel."), ["second_conditional"]);
+        assert_eq!(found("Should you need help, we will help you."), ["first_conditional"]);
+        assert!(found("Had you seen it?").iter().all(|id| !id.ends_with("conditional")));
+    }
+
+    #[test]
+    fn if_meaning_whether_is_no_conditional() {
+        assert!(found("I wonder if he knows.").is_empty());
+        assert!(found("Ask him if he wants tea.").is_empty());
+        assert!(found("He acts as if he owns the place.").is_empty());
+    }
+
+    #[test]
+    fn reported_speech_with_and_without_a_hearer() {
+        assert_eq!(found("She said that she was tired."), ["reported_speech"]);
+        assert_eq!(found("He told me he would come."), ["reported_speech"]);
+        assert_eq!(found("He said he was going to call."), ["reported_speech"]);
+        assert_eq!(found("She asked me where I lived."), ["reported_speech"]);
+        assert!(found("He asked for help.").is_empty());
+    }
+
+    #[test]
+    fn passives_relatives_clefts_and_inversion() {
+        assert_eq!(found("It should have been done yesterday."), ["passive_modal"]);
+        assert_eq!(found("My brother, who lives in Paris, is a doctor."), ["non_defining_relative"]);
+        assert_eq!(found("It was John who called."), ["cleft_sentence"]);
+        assert_eq!(found("What I need is a holiday."), ["cleft_sentence"]);
+        assert!(found("What she said was true.").is_empty());
+        assert!(found("It is important that you come.").is_empty());
+        assert_eq!(found("Not only did she win, she broke the record."), ["negative_inversion"]);
+        assert!(found("Never do that again.").is_empty());
+    }
+
+    #[test]
+    fn the_level_averages_the_hardest_structures() {
+        let sentences = [tagged("Never have I seen such a mess."), tagged("I have lived here."), tagged("It was John who called.")];
+        let to_offsets = |r: Range<usize>| Offsets { start: r.start, end: r.end, start_utf16: r.start, end_utf16: r.end };
+        let metrics = GrammarAnalyzer::analyze(&sentences, to_offsets);
+        let ids: Vec<&str> = metrics.structures.iter().map(|s| s.id).collect();
+        assert_eq!(ids, ["present_perfect", "cleft_sentence", "negative_inversion"]);
+        let expected = [CEFRLevel::A2, CEFRLevel::B2, CEFRLevel::C1].iter().map(level_to_score).sum::<f64>() / 3.0;
+        assert_eq!(metrics.score, expected);
+        assert_eq!(metrics.level, Some(score_to_level(expected)));
+        let example = &metrics.structures[0].examples[0];
+        assert_eq!((example.sentence, example.offsets.start, example.offsets.end), (1, 2, 12));
+        assert_eq!(GrammarAnalyzer::analyze(&[tagged("The cat sat.")], to_offsets).level, None);
+    }
+}
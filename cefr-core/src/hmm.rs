@@ -0,0 +1,288 @@
+//! Second-order hidden Markov model for part-of-speech tagging, after TnT
+//! (Brants 2000).
+//!
+//! Shared by the library (decoding with the embedded assets/pos_model.json)
+//! and src/bin/train_tagger.rs, which estimates the model from a corpus of
+//! `word/TAG` sentences. A tag depends on the two before it, with trigram,
+//! bigram and unigram estimates interpolated by weights set with deleted
+//! interpolation. Unknown words are scored with a TnT-style suffix model
+//! kept separately for capitalized and lowercase words.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+
+/// Longest suffix used for unknown words
+pub const MAX_SUFFIX: usize = 4;
+/// Words seen at most this often feed the suffix model
+const RARE_WORD_COUNT: u32 = 5;
+
+#[derive(Serialize, Deserialize)]
+pub struct HmmModel {
+    pub tags: Vec<String>,
+    /// P(tag | previous tag); the extra last row is the sentence start
+    bigrams: Vec<Vec<f32>>,
+    /// P(tag | two previous tags) for the tag pairs seen, indexed like
+    /// `bigrams` on both levels (the start pads the first two positions)
+    trigrams: Vec<Vec<Vec<(u16, f32)>>>,
+    /// Weights of the unigram, bigram and trigram estimates
+    lambdas: [f32; 3],
+    /// Known words: log P(tag | word) / P(tag) for each observed tag. Maps
+    /// here are ordered so retraining on the same corpus rewrites the same file
+    lexicon: BTreeMap<String, Vec<(u16, f32)>>,
+    /// Tag counts of rare words by suffix, keyed "u:" or "l:" (capitalized or
+    /// not) plus the lowercased suffix; the bare prefix holds the class totals
+    suffixes: BTreeMap<String, Vec<(u16, u32)>>,
+    /// log P(tag) over all tokens
+    tag_log_prior: Vec<f32>,
+    /// Weight of the shorter suffix when interpolating
+    theta: f32,
+}
+
+fn suffix_key(capitalized: bool, lower: &str, len: usize) -> Option<String> {
+    let start = lower.char_indices().rev().nth(len.checked_sub(1)?).map(|(i, _)| i)?;
+    Some(format!("{}{}", if capitalized { "u:" } else { "l:" }, &lower[start..]))
+}
+
+fn class_key(capitalized: bool) -> String {
+    (if capitalized { "u:" } else { "l:" }).to_string()
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// Unknown words are practically always nouns, verbs, adjectives, adverbs
+/// or numbers, so only these tags feed the suffix model.
+fn is_open_class_tag(tag: &str) -> bool {
+    matches!(tag.as_bytes().first(), Some(b'N' | b'V' | b'J' | b'R')) || tag == "CD" || tag == "FW"
+}
+
+impl HmmModel {
+    /// Estimate a model from tagged sentences.
+    pub fn train(sentences: &[Vec<(String, String)>]) -> HmmModel {
+        let mut tags: Vec<String> = sentences.iter().flatten().map(|(_, t)| t.clone()).collect();
+        tags.sort();
+        tags.dedup();
+        let index: HashMap<&str, usize> = tags.iter().enumerate().map(|(i, t)| (t.as_str(), i)).collect();
+        let n = tags.len();
+
+        let mut tag_counts = vec![0u32; n];
+        let mut bigram_counts = vec![vec![0u32; n]; n + 1];
+        let mut trigram_counts: HashMap<(usize, usize, usize), u32> = HashMap::new();
+        let mut word_tags: HashMap<&str, Vec<u32>> = HashMap::new();
+        for sentence in sentences {
+            let (mut prev2, mut prev) = (n, n);
+            for (word, tag) in sentence {
+                let t = index[tag.as_str()];
+                tag_counts[t] += 1;
+                bigram_counts[prev][t] += 1;
+                *trigram_counts.entry((prev2, prev, t)).or_default() += 1;
+                word_tags.entry(word.as_str()).or_insert_with(|| vec![0; n])[t] += 1;
+                (prev2, prev) = (prev, t);
+            }
+        }
+
+        let total: u32 = tag_counts.iter().sum();
+        let prior: Vec<f32> = tag_counts.iter().map(|&c| c as f32 / total as f32).collect();
+        let tag_log_prior = prior.iter().map(|p| p.ln()).collect();
+
+        // Times each tag and tag pair was followed by another tag
+        let context_counts: Vec<u32> = bigram_counts.iter().map(|row| row.iter().sum()).collect();
+        let mut pair_counts: HashMap<(usize, usize), u32> = HashMap::new();
+        for (&(a, b, _), &c) in &trigram_counts {
+            *pair_counts.entry((a, b)).or_default() += c;
+        }
+
+        let bigrams = bigram_counts
+            .iter()
+            .zip(&context_counts)
+            .map(|(row, &row_total)| row.iter().map(|&c| c as f32 / row_total.max(1) as f32).collect())
+            .collect();
+        let mut trigrams = vec![vec![Vec::new(); n + 1]; n + 1];
+        let mut lambda_counts = [0u32; 3];
+        let mut seen: Vec<_> = trigram_counts.iter().collect();
+        seen.sort();
+        for (&(a, b, t), &c) in seen {
+            trigrams[a][b].push((t as u16, c as f32 / pair_counts[&(a, b)] as f32));
+            // Deleted interpolation: credit the estimate that best predicts
+            // this trigram with one occurrence of it left out
+            let ratio = |num: u32, denom: u32| if denom > 1 { (num - 1) as f32 / (denom - 1) as f32 } else { 0.0 };
+            let estimates = [
+                ratio(tag_counts[t], total),
+                ratio(bigram_counts[b][t], context_counts[b]),
+                ratio(c, pair_counts[&(a, b)]),
+            ];
+            let best = (0..3).rev().max_by(|&x, &y| estimates[x].total_cmp(&estimates[y])).unwrap_or(0);
+            lambda_counts[best] += c;
+        }
+        let lambda_total = lambda_counts.iter().sum::<u32>().max(1) as f32;
+        let lambdas = lambda_counts.map(|c| c as f32 / lambda_total);
+
+        let mut lexicon = BTreeMap::new();
+        for (word, counts) in &word_tags {
+            let word_total: u32 = counts.iter().sum();
+            let entry = counts
+                .iter()
+                .enumerate()
+                .filter(|&(_, &c)| c > 0)
+                .map(|(t, &c)| (t as u16, (c as f32 / word_total as f32 / prior[t]).ln()))
+                .collect();
+            lexicon.insert(word.to_string(), entry);
+        }
+
+        // Suffix model over rare open-class tokens. Sentence case says nothing,
+        // so a capitalized first word counts as capitalized only if it's a name.
+        let mut suffix_counts: HashMap<String, Vec<u32>> = HashMap::new();
+        for sentence in sentences {
+            for (i, (word, tag)) in sentence.iter().enumerate() {
+                let word_total: u32 = word_tags[word.as_str()].iter().sum();
+                if word_total > RARE_WORD_COUNT || !is_open_class_tag(tag) || !word.chars().any(char::is_alphabetic) {
+                    continue;
+                }
+                let capitalized = is_capitalized(word) && (i > 0 || tag.starts_with("NNP"));
+                let lower = word.to_lowercase();
+                let keys = std::iter::once(class_key(capitalized))
+                    .chain((1..=MAX_SUFFIX).filter_map(|len| suffix_key(capitalized, &lower, len)));
+                for key in keys {
+                    suffix_counts.entry(key).or_insert_with(|| vec![0; n])[index[tag.as_str()]] += 1;
+                }
+            }
+        }
+        let suffixes = suffix_counts
+            .into_iter()
+            .map(|(key, counts)| {
+                let sparse = counts.iter().enumerate().filter(|&(_, &c)| c > 0).map(|(t, &c)| (t as u16, c)).collect();
+                (key, sparse)
+            })
+            .collect();
+
+        // TnT: theta is the standard deviation of the tag probabilities
+        let mean = 1.0 / n as f32;
+        let theta = (prior.iter().map(|p| (p - mean).powi(2)).sum::<f32>() / (n.max(2) - 1) as f32).sqrt();
+
+        HmmModel { tags, bigrams, trigrams, lambdas, lexicon, suffixes, tag_log_prior, theta }
+    }
+
+    pub fn tag_index(&self, tag: &str) -> Option<usize> {
+        self.tags.iter().position(|t| t == tag)
+    }
+
+    /// Scores of the tags a corpus word was seen with, trying the exact
+    /// form and then the lowercased one. `None` for unknown words.
+    pub fn known_emissions(&self, word: &str) -> Option<Vec<(usize, f32)>> {
+        let entry = self.lexicon.get(word).or_else(|| self.lexicon.get(&word.to_lowercase()))?;
+        Some(entry.iter().map(|&(t, score)| (t as usize, score)).collect())
+    }
+
+    /// Scores of the open-class tags for an unknown word from its longest
+    /// known suffix, on the same log P(tag | word) / P(tag) scale as known words.
+    pub fn suffix_emissions(&self, word: &str, capitalized: bool) -> Vec<(usize, f32)> {
+        let n = self.tags.len();
+        let lower = word.to_lowercase();
+        let mut probs: Vec<f32> = self.tag_log_prior.iter().map(|p| p.exp()).collect();
+
+        let keys = std::iter::once(class_key(capitalized))
+            .chain((1..=MAX_SUFFIX).filter_map(|len| suffix_key(capitalized, &lower, len)));
+        for key in keys {
+            let Some(counts) = self.suffixes.get(&key) else { break };
+            let total: u32 = counts.iter().map(|&(_, c)| c).sum();
+            let mut observed = vec![0.0f32; n];
+            for &(t, c) in counts {
+                observed[t as usize] = c as f32 / total as f32;
+            }
+            for t in 0..n {
+                probs[t] = (observed[t] + self.theta * probs[t]) / (1.0 + self.theta);
+            }
+        }
+
+        probs
+            .iter()
+            .enumerate()
+            .filter(|&(t, _)| is_open_class_tag(&self.tags[t]))
+            .map(|(t, p)| (t, p.ln() - self.tag_log_prior[t]))
+            .collect()
+    }
+
+    /// log P(`tag` | `prev2`, `prev`), with `tags.len()` for the sentence start
+    fn transition(&self, prev2: usize, prev: usize, tag: usize) -> f32 {
+        let trigram = self.trigrams[prev2][prev].iter().find(|&&(t, _)| t as usize == tag).map_or(0.0, |&(_, p)| p);
+        let [unigram_weight, bigram_weight, trigram_weight] = self.lambdas;
+        (unigram_weight * self.tag_log_prior[tag].exp() + bigram_weight * self.bigrams[prev][tag] + trigram_weight * trigram).ln()
+    }
+
+    /// Most likely tag sequence given each position's candidate tags and
+    /// emission scores. Positions with no candidates get `usize::MAX`.
+    pub fn viterbi(&self, candidates: &[Vec<(usize, f32)>]) -> Vec<usize> {
+        let start = self.tags.len();
+        let positions: Vec<usize> = (0..candidates.len()).filter(|&i| !candidates[i].is_empty()).collect();
+        let mut path = vec![usize::MAX; candidates.len()];
+        if positions.is_empty() {
+            return path;
+        }
+
+        // Tags of the candidate at index k of a position; the start before the first
+        let tag_at = |j: Option<usize>, k: usize| j.map_or(start, |j| candidates[positions[j]][k].0);
+        // best[j][p * width + c]: score of candidate c at position j after
+        // candidate p at position j - 1, and the back-pointer into j - 2
+        let width = |j: Option<usize>| j.map_or(1, |j| candidates[positions[j]].len());
+        let mut best: Vec<Vec<(f32, usize)>> = Vec::with_capacity(positions.len());
+        for j in 0..positions.len() {
+            let prev = j.checked_sub(1);
+            let prev2 = j.checked_sub(2);
+            let current = &candidates[positions[j]];
+            let mut column = Vec::with_capacity(width(prev) * current.len());
+            for p in 0..width(prev) {
+                for &(tag, emission) in current {
+                    let from = (0..width(prev2))
+                        .map(|q| {
+                            let before = match prev {
+                                Some(prev) => best[prev][q * width(Some(prev)) + p].0,
+                                None => 0.0,
+                            };
+                            (before + self.transition(tag_at(prev2, q), tag_at(prev, p), tag), q)
+                        })
+                        .max_by(|a, b| a.0.total_cmp(&b.0))
+                        .unwrap_or((f32::NEG_INFINITY, 0));
+                    column.push((from.0 + emission, from.1));
+                }
+            }
+            best.push(column);
+        }
+
+        let last = positions.len() - 1;
+        let cur_width = candidates[positions[last]].len();
+        let end = (0..best[last].len()).max_by(|&a, &b| best[last][a].0.total_cmp(&best[last][b].0)).unwrap_or(0);
+        let (mut p, mut c) = (end / cur_width, end % cur_width);
+        for j in (0..positions.len()).rev() {
+            path[positions[j]] = candidates[positions[j]][c].0;
+            let back = best[j][p * candidates[positions[j]].len() + c].1;
+            (c, p) = (p, back);
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::parse_tagged_corpus;
+
+    #[test]
+    fn training_twice_writes_the_same_model() {
+        let corpus = parse_tagged_corpus("The/DT dog/NN barks/VBZ ./.\nA/DT cat/NN sleeps/VBZ ./.\nDogs/NNS bark/VBP loudly/RB ./.").unwrap();
+        let first = serde_json::to_string(&HmmModel::train(&corpus)).unwrap();
+        let second = serde_json::to_string(&HmmModel::train(&corpus)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn unknown_words_are_scored_by_suffix() {
+        let corpus = parse_tagged_corpus("He/PRP walked/VBD home/RB ./.\nShe/PRP talked/VBD ./.\nThe/DT walk/NN ./.").unwrap();
+        let model = HmmModel::train(&corpus);
+        let scores = model.suffix_emissions("jumped", false);
+        let best = scores.iter().max_by(|a, b| a.1.total_cmp(&b.1)).map(|&(t, _)| model.tags[t].as_str());
+        assert_eq!(best, Some("VBD"));
+        assert!(model.known_emissions("Walk").is_some());
+    }
+}
@@ -0,0 +1,470 @@
+use phf::phf_map;
+
+/// Irregular verb forms (past tense, past participle and a few present forms) -> base form.
+static IRREGULAR_VERBS: phf::Map<&'static str, &'static str> = phf_map! {
+    "am" => "be", "is" => "be", "are" => "be", "was" => "be", "were" => "be", "been" => "be", "being" => "be",
+    "has" => "have", "had" => "have", "having" => "have",
+    "does" => "do", "did" => "do", "done" => "do",
+    "arose" => "arise", "arisen" => "arise",
+    "awoke" => "awake", "awoken" => "awake",
+    "bore" => "bear", "borne" => "bear", "born" => "bear",
+    "beat" => "beat", "beaten" => "beat",
+    "became" => "become",
+    "began" => "begin", "begun" => "begin",
+    "bent" => "bend",
+    "bet" => "bet",
+    "bound" => "bind",
+    "bit" => "bite", "bitten" => "bite",
+    "bled" => "bleed",
+    "blew" => "blow", "blown" => "blow",
+    "broke" => "break", "broken" => "break",
+    "bred" => "breed",
+    "brought" => "bring",
+    "built" => "build",
+    "burnt" => "burn",
+    "burst" => "burst",
+    "bought" => "buy",
+    "caught" => "catch",
+    "chose" => "choose", "chosen" => "choose",
+    "clung" => "cling",
+    "came" => "come",
+    "cost" => "cost",
+    "crept" => "creep",
+    "dealt" => "deal",
+    "dug" => "dig",
+    "drew" => "draw", "drawn" => "draw",
+    "dreamt" => "dream",
+    "drank" => "drink", "drunk" => "drink",
+    "drove" => "drive", "driven" => "drive",
+    "ate" => "eat", "eaten" => "eat",
+    "fell" => "fall", "fallen" => "fall",
+    "fed" => "feed",
+    "felt" => "feel",
+    "fought" => "fight",
+    "found" => "find",
+    "fled" => "flee",
+    "flung" => "fling",
+    "flew" => "fly", "flown" => "fly",
+    "forbade" => "forbid", "forbidden" => "forbid",
+    "forgot" => "forget", "forgotten" => "forget",
+    "forgave" => "forgive", "forgiven" => "forgive",
+    "froze" => "freeze", "frozen" => "freeze",
+    "got" => "get", "gotten" => "get",
+    "gave" => "give", "given" => "give",
+    "went" => "go", "gone" => "go",
+    "ground" => "grind",
+    "grew" => "grow", "grown" => "grow",
+    "hung" => "hang",
+    "heard" => "hear",
+    "hid" => "hide", "hidden" => "hide",
+    "held" => "hold",
+    "hurt" => "hurt",
+    "kept" => "keep",
+    "knelt" => "kneel",
+    "knew" => "know", "known" => "know",
+    "laid" => "lay",
+    "led" => "lead",
+    "leapt" => "leap",
+    "learnt" => "learn",
+    "left" => "leave",
+    "lent" => "lend",
+    "lay" => "lie", "lain" => "lie",
+    "lit" => "light",
+    "lost" => "lose",
+    "made" => "make",
+    "meant" => "mean",
+    "met" => "meet",
+    "mistook" => "mistake", "mistaken" => "mistake",
+    "paid" => "pay",
+    "proven" => "prove",
+    "quit" => "quit",
+    "rode" => "ride", "ridden" => "ride",
+    "rang" => "ring", "rung" => "ring",
+    "rose" => "rise", "risen" => "rise",
+    "ran" => "run",
+    "said" => "say",
+    "saw" => "see", "seen" => "see",
+    "sought" => "seek",
+    "sold" => "sell",
+    "sent" => "send",
+    "shook" => "shake", "shaken" => "shake",
+    "shone" => "shine",
+    "shot" => "shoot",
+    "showed" => "show", "shown" => "show",
+    "shrank" => "shrink", "shrunk" => "shrink",
+    "shut" => "shut",
+    "sang" => "sing", "sung" => "sing",
+    "sank" => "sink", "sunk" => "sink",
+    "sat" => "sit",
+    "slept" => "sleep",
+    "slid" => "slide",
+    "spoke" => "speak", "spoken" => "speak",
+    "sped" => "speed",
+    "spent" => "spend",
+    "spun" => "spin",
+    "split" => "split",
+    "spread" => "spread",
+    "sprang" => "spring", "sprung" => "spring",
+    "stood" => "stand",
+    "stole" => "steal", "stolen" => "steal",
+    "stuck" => "stick",
+    "stung" => "sting",
+    "stank" => "stink",
+    "strode" => "stride",
+    "struck" => "strike", "stricken" => "strike",
+    "strove" => "strive", "striven" => "strive",
+    "swore" => "swear", "sworn" => "swear",
+    "swept" => "sweep",
+    "swam" => "swim", "swum" => "swim",
+    "swung" => "swing",
+    "took" => "take", "taken" => "take",
+    "taught" => "teach",
+    "tore" => "tear", "torn" => "tear",
+    "told" => "tell",
+    "thought" => "think",
+    "threw" => "throw", "thrown" => "throw",
+    "understood" => "understand",
+    "undertook" => "undertake", "undertaken" => "undertake",
+    "upset" => "upset",
+    "woke" => "wake", "woken" => "wake",
+    "wore" => "wear", "worn" => "wear",
+    "wove" => "weave", "woven" => "weave",
+    "wept" => "weep",
+    "won" => "win",
+    "wound" => "wind",
+    "withdrew" => "withdraw", "withdrawn" => "withdraw",
+    "wrote" => "write", "written" => "write",
+};
+
+/// Irregular plurals -> singular.
+static IRREGULAR_NOUNS: phf::Map<&'static str, &'static str> = phf_map! {
+    "children" => "child", "men" => "man", "women" => "woman", "people" => "person",
+    "mice" => "mouse", "lice" => "louse", "geese" => "goose", "feet" => "foot", "teeth" => "tooth",
+    "oxen" => "ox", "dice" => "die",
+    "lives" => "life", "knives" => "knife", "wives" => "wife", "leaves" => "leaf", "halves" => "half",
+    "wolves" => "wolf", "shelves" => "shelf", "selves" => "self", "loaves" => "loaf", "thieves" => "thief",
+    "calves" => "calf", "scarves" => "scarf", "sheaves" => "sheaf", "elves" => "elf",
+    "analyses" => "analysis", "crises" => "crisis", "theses" => "thesis", "hypotheses" => "hypothesis",
+    "diagnoses" => "diagnosis", "bases" => "basis", "oases" => "oasis", "parentheses" => "parenthesis",
+    "phenomena" => "phenomenon", "criteria" => "criterion",
+    "cacti" => "cactus", "fungi" => "fungus", "nuclei" => "nucleus", "stimuli" => "stimulus",
+    "syllabi" => "syllabus", "alumni" => "alumnus",
+    "appendices" => "appendix", "indices" => "index", "matrices" => "matrix", "vertices" => "vertex",
+    "bacteria" => "bacterium", "curricula" => "curriculum", "memoranda" => "memorandum",
+};
+
+/// Irregular comparatives and superlatives -> positive form.
+static IRREGULAR_ADJECTIVES: phf::Map<&'static str, &'static str> = phf_map! {
+    "better" => "good", "best" => "good",
+    "worse" => "bad", "worst" => "bad",
+    "further" => "far", "furthest" => "far", "farther" => "far", "farthest" => "far",
+    "less" => "little", "least" => "little",
+    "more" => "much", "most" => "much",
+    "elder" => "old", "eldest" => "old",
+};
+
+/// Which inflection rules a POS tag allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WordClass {
+    Noun,
+    Verb,
+    Adjective,
+    Any,
+}
+
+/// Which verb inflection a tag points at (fine-grained PTB tags narrow the rules).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VerbForm {
+    ThirdPerson, // VBZ
+    Past,        // VBD, VBN
+    Gerund,      // VBG
+    Any,         // VB, VBP
+}
+
+fn word_class(tag: Option<&str>) -> (WordClass, VerbForm) {
+    match tag {
+        Some("VBZ") => (WordClass::Verb, VerbForm::ThirdPerson),
+        Some("VBD") | Some("VBN") => (WordClass::Verb, VerbForm::Past),
+        Some("VBG") => (WordClass::Verb, VerbForm::Gerund),
+        Some(t) if t.starts_with('V') => (WordClass::Verb, VerbForm::Any),
+        Some(t) if t.starts_with('N') => (WordClass::Noun, VerbForm::Any),
+        Some(t) if t.starts_with('J') || t.starts_with('R') => (WordClass::Adjective, VerbForm::Any),
+        _ => (WordClass::Any, VerbForm::Any),
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Consonants English doubles before -ed/-ing/-er ("stopped", "running", "bigger").
+fn is_doubling_consonant(c: char) -> bool {
+    matches!(c, 'b' | 'd' | 'g' | 'k' | 'l' | 'm' | 'n' | 'p' | 'r' | 't' | 'v' | 'z')
+}
+
+/// Stems left after stripping -ed/-ing/-er/-est, most likely first:
+/// "stopp" -> stop, "mak" -> make, "hop" -> hope, "walk" -> walk.
+fn restore_stem(stem: &str, out: &mut Vec<String>) {
+    let chars: Vec<char> = stem.chars().collect();
+    let n = chars.len();
+    if n < 2 {
+        return;
+    }
+
+    let syllables = chars.windows(2).filter(|w| is_vowel(w[1]) && !is_vowel(w[0])).count()
+        + usize::from(is_vowel(chars[0]));
+
+    // Consonant doubling: "stopp" -> "stop"
+    if n >= 3 && chars[n - 1] == chars[n - 2] && is_doubling_consonant(chars[n - 1]) && chars[n - 1] != 'l' {
+        out.push(chars[..n - 1].iter().collect());
+        out.push(stem.to_string());
+        return;
+    }
+    // British -ll- after an unstressed final syllable ("travell" -> travel,
+    // "controll" -> control); one-syllable "call", "fill" and -all words
+    // ("install") keep it
+    if n >= 4 && chars[n - 1] == 'l' && chars[n - 2] == 'l' {
+        let undoubled: String = chars[..n - 1].iter().collect();
+        if syllables >= 2 && chars[n - 3] != 'a' {
+            out.push(undoubled);
+            out.push(stem.to_string());
+        } else {
+            out.push(stem.to_string());
+            out.push(undoubled);
+        }
+        return;
+    }
+
+    // e-drop: a one-syllable consonant-vowel-consonant stem usually lost an
+    // "e" ("hoping" -> hope, "making" -> make); longer ones rarely did
+    // ("visiting" -> visit). Callers with a dictionary try both.
+    let cvc = n >= 3 && syllables == 1
+        && !is_vowel(chars[n - 1]) && is_vowel(chars[n - 2]) && !is_vowel(chars[n - 3])
+        && !matches!(chars[n - 1], 'w' | 'x' | 'y');
+    // Consonant + l/r can't end a stem: "handl" -> handle, "centr" -> centre
+    // (but "curl", "howl")
+    let syllabic_ending = matches!(chars[n - 1], 'l' | 'r') && !is_vowel(chars[n - 2]) && !matches!(chars[n - 2], 'l' | 'r' | 'w' | 'y');
+    let needs_e = matches!(chars[n - 1], 'v' | 'c' | 'z' | 'u')
+        || syllabic_ending
+        || matches!(chars[n - 2..].iter().collect::<String>().as_str(), "at" | "iz" | "dg" | "rs" | "ls");
+    if cvc || needs_e {
+        out.push(format!("{}e", stem));
+        out.push(stem.to_string());
+    } else {
+        out.push(stem.to_string());
+        out.push(format!("{}e", stem));
+    }
+}
+
+/// -s / -es / -ies / -ves plurals and third-person forms. -ves -> f only
+/// fits nouns: a verb (VBZ) never takes it ("moves" is move, not "mof"), and
+/// an untagged word tries the plain -s reading first.
+fn strip_plural(word: &str, class: WordClass, out: &mut Vec<String>) {
+    let noun = class != WordClass::Verb;
+    if let Some(stem) = word.strip_suffix("ies") {
+        if stem.len() >= 2 {
+            out.push(format!("{}y", stem));
+        }
+    }
+    // "buses", "campuses", "viruses": the noun ends in -us (but "causes",
+    // "houses", "uses" keep their e below)
+    if let Some(stem) = word.strip_suffix("uses") {
+        if noun && stem.chars().last().is_some_and(|c| !is_vowel(c)) {
+            out.push(format!("{}us", stem));
+        }
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        if stem.ends_with("ss") || stem.ends_with("sh") || stem.ends_with("ch") || stem.ends_with('x')
+            || stem.ends_with('z') || stem.ends_with('o') {
+            out.push(stem.to_string());
+        }
+    }
+    let ves_stem = word.strip_suffix("ves").filter(|_| noun);
+    if class == WordClass::Noun {
+        if let Some(stem) = ves_stem {
+            out.push(format!("{}f", stem));
+            out.push(format!("{}fe", stem));
+        }
+    }
+    if let Some(stem) = word.strip_suffix('s') {
+        if !stem.is_empty() && !stem.ends_with('s') && !word.ends_with("us") && !word.ends_with("is") {
+            out.push(stem.to_string());
+        }
+    }
+    if class == WordClass::Any {
+        if let Some(stem) = ves_stem {
+            out.push(format!("{}f", stem));
+            out.push(format!("{}fe", stem));
+        }
+    }
+    // "gases"; "houses"/"causes" -> house/cause above
+    if let Some(stem) = word.strip_suffix("ses") {
+        if !stem.ends_with('s') {
+            out.push(format!("{}s", stem));
+        }
+    }
+}
+
+fn strip_past(word: &str, out: &mut Vec<String>) {
+    if let Some(stem) = word.strip_suffix("ied") {
+        if stem.len() >= 2 {
+            out.push(format!("{}y", stem));
+        }
+    }
+    if let Some(stem) = word.strip_suffix("ed") {
+        // -eed: "agreed" -> agree, "guaranteed" -> guarantee
+        if stem.len() >= 3 && stem.ends_with('e') {
+            out.push(format!("{}e", stem));
+        }
+        if stem.len() >= 2 {
+            restore_stem(stem, out);
+        }
+    }
+}
+
+fn strip_gerund(word: &str, out: &mut Vec<String>) {
+    if let Some(stem) = word.strip_suffix("ying") {
+        // lying -> lie, dying -> die (studying keeps its y below)
+        if stem.len() <= 2 {
+            out.push(format!("{}ie", stem));
+        }
+    }
+    if let Some(stem) = word.strip_suffix("ing") {
+        if stem.len() >= 2 {
+            restore_stem(stem, out);
+        }
+    }
+}
+
+fn strip_comparative(word: &str, out: &mut Vec<String>) {
+    for suffix in ["iest", "ier"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.len() >= 2 {
+                out.push(format!("{}y", stem));
+            }
+        }
+    }
+    for suffix in ["est", "er"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.len() >= 2 {
+                restore_stem(stem, out);
+            }
+        }
+    }
+}
+
+/// Candidate lemmas for `word`, most likely first, never including `word` itself.
+/// `tag` (a PTB tag from the tagger) restricts which rules apply; `None` tries them all.
+pub fn lemma_candidates(word: &str, tag: Option<&str>) -> Vec<String> {
+    let lower = word.to_lowercase();
+    let (class, form) = word_class(tag);
+    let mut out = Vec::new();
+
+    let verb = matches!(class, WordClass::Verb | WordClass::Any);
+    let noun = matches!(class, WordClass::Noun | WordClass::Any);
+    let adjective = matches!(class, WordClass::Adjective | WordClass::Any);
+
+    // Irregular tables first
+    if verb {
+        if let Some(base) = IRREGULAR_VERBS.get(lower.as_str()) {
+            out.push(base.to_string());
+        }
+    }
+    if noun {
+        if let Some(base) = IRREGULAR_NOUNS.get(lower.as_str()) {
+            out.push(base.to_string());
+        }
+    }
+    if adjective {
+        if let Some(base) = IRREGULAR_ADJECTIVES.get(lower.as_str()) {
+            out.push(base.to_string());
+        }
+    }
+
+    // Regular suffix rules
+    if verb && matches!(form, VerbForm::Past | VerbForm::Any) {
+        strip_past(&lower, &mut out);
+    }
+    if verb && matches!(form, VerbForm::Gerund | VerbForm::Any) {
+        strip_gerund(&lower, &mut out);
+    }
+    if noun || (verb && matches!(form, VerbForm::ThirdPerson | VerbForm::Any)) {
+        strip_plural(&lower, class, &mut out);
+    }
+    if adjective {
+        strip_comparative(&lower, &mut out);
+    }
+
+    let mut seen = Vec::with_capacity(out.len());
+    out.retain(|c| c != &lower && !seen.contains(c) && {
+        seen.push(c.clone());
+        true
+    });
+    out
+}
+
+/// Best-guess lemma without a dictionary: the first candidate, or the word itself.
+pub fn lemmatize(word: &str, tag: Option<&str>) -> String {
+    lemma_candidates(word, tag)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| word.to_lowercase())
+}
+
+/// Whether `word` looks like a past tense or past participle ("walked", "gone", "been").
+pub fn is_past_form(word: &str) -> bool {
+    let lower = word.to_lowercase();
+    if IRREGULAR_VERBS.contains_key(lower.as_str()) {
+        return !matches!(lower.as_str(), "am" | "is" | "are" | "has" | "does" | "being" | "having");
+    }
+    lower.len() > 3 && lower.ends_with("ed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn third_person_verbs_do_not_take_the_ves_rule() {
+        assert_eq!(lemmatize("moves", Some("VBZ")), "move");
+        assert_eq!(lemmatize("saves", Some("VBZ")), "save");
+        assert_eq!(lemmatize("lives", Some("VBZ")), "live");
+        assert_eq!(lemmatize("moves", None), "move");
+        assert_eq!(lemmatize("hooves", Some("NNS")), "hoof");
+        assert_eq!(lemmatize("lives", Some("NNS")), "life");
+    }
+
+    #[test]
+    fn past_forms_restore_their_stem() {
+        assert_eq!(lemmatize("agreed", Some("VBD")), "agree");
+        assert_eq!(lemmatize("travelled", Some("VBD")), "travel");
+        assert_eq!(lemmatize("controlled", Some("VBN")), "control");
+        assert_eq!(lemmatize("called", Some("VBD")), "call");
+        assert_eq!(lemmatize("installed", Some("VBD")), "install");
+        assert_eq!(lemmatize("centred", Some("VBN")), "centre");
+        assert_eq!(lemmatize("handled", Some("VBD")), "handle");
+        assert_eq!(lemmatize("stopped", Some("VBD")), "stop");
+        assert_eq!(lemmatize("hoped", Some("VBD")), "hope");
+        assert_eq!(lemmatize("needed", Some("VBD")), "need");
+        assert_eq!(lemmatize("studied", Some("VBD")), "study");
+    }
+
+    #[test]
+    fn plurals_in_ses() {
+        assert_eq!(lemmatize("buses", Some("NNS")), "bus");
+        assert_eq!(lemmatize("campuses", None), "campus");
+        assert_eq!(lemmatize("houses", Some("NNS")), "house");
+        assert_eq!(lemmatize("causes", Some("NNS")), "cause");
+        assert_eq!(lemmatize("boxes", Some("NNS")), "box");
+        assert_eq!(lemmatize("cities", Some("NNS")), "city");
+    }
+
+    #[test]
+    fn candidates_follow_the_tag() {
+        assert_eq!(lemmatize("left", Some("VBD")), "leave");
+        assert!(lemma_candidates("left", Some("JJ")).is_empty());
+        assert_eq!(lemmatize("better", Some("JJR")), "good");
+        assert_eq!(lemmatize("running", Some("VBG")), "run");
+        assert!(is_past_form("walked") && is_past_form("gone") && !is_past_form("is"));
+    }
+}
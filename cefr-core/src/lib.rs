@@ -1,21 +1,52 @@
+mod dict_source;
 mod dictionary;
 mod pos;
 mod syntax;
 mod discourse;
 mod fst_dict;
+mod exchange;
+mod lemmatizer;
+mod phrases;
+mod phrasal;
+mod tokenizer;
+mod segmenter;
+mod normalize;
+mod clitics;
+mod offsets;
+mod hmm;
+mod brill;
+mod corpus;
+mod pos_eval;
+mod senses;
+mod grammar;
+mod verb_groups;
+mod parser;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use wasm_bindgen::prelude::*;
 use serde::Serialize;
-use rust_stemmers::{Algorithm, Stemmer};
 use dictionary::{DICT, CEFRLevel};
+use lemmatizer::lemmatize;
+use phrases::token_forms;
+use phrasal::detect_phrasal_verbs;
 use pos::tag_sentence;
+use senses::select_sense;
+pub use pos::{tag_gold_words, GoldPrediction, TaggerMode};
+pub use pos_eval::{evaluate_pos, PosEvaluation, TagScore, WordErrors};
+use tokenizer::tokenize_sentence;
+use segmenter::split_sentences;
+use normalize::normalize;
+use clitics::Clitic;
+use offsets::{Offsets, Utf16Index};
 use syntax::{SyntacticAnalyzer, SyntaxMetrics};
 use discourse::{DiscourseAnalyzer, DiscourseMetrics, is_common_name};
+use grammar::{GrammarAnalyzer, GrammarMetrics};
+use verb_groups::{chunk_verb_groups, Aspect, Tense, TenseDistribution, Voice};
 
 // Weights for adjusted score
 const WEIGHT_CLAUSE_DENSITY: f64 = 0.5;
 const WEIGHT_CONNECTIVE_SOPHISTICATION: f64 = 0.5;
+const WEIGHT_GRAMMAR_LEVEL: f64 = 0.5;
 
 #[derive(Serialize)]
 struct AnalysisResult {
@@ -24,6 +55,9 @@ struct AnalysisResult {
     adjusted_score: f64,   // New: Final score after syntax/discourse
     metrics: CombinedMetrics,
     details: Vec<TokenDetail>,
+    phrases: Vec<PhraseSpan>,
+    verb_groups: Vec<VerbGroupSpan>,
+    sentences: Vec<SentenceSpan>,
 }
 
 #[derive(Serialize)]
@@ -34,6 +68,8 @@ struct CombinedMetrics {
     avg_sentence_length: f64,
     syntax: SyntaxMetrics,
     discourse: DiscourseMetrics,
+    grammar: GrammarMetrics,
+    tenses: TenseDistribution,
 }
 
 #[derive(Serialize)]
@@ -43,14 +79,85 @@ struct TokenDetail {
     pos: String,
     level: String,
     is_phrase: bool,
+    phrase: Option<usize>,        // Index into `AnalysisResult.phrases`
+    source: Option<&'static str>, // Word list that supplied `level`
+    sense: Option<&'static str>,  // Gloss of the sense that set `level`, for polysemous words
+    kind: &'static str,           // "word", "number", "ordinal", "year", "currency", "url" or "email"
+    clitic: Option<&'static str>, // "is"/"has"/"had"/"would" for 's/'d, "possessive" on a possessor
+    offsets: Offsets,
+}
+
+/// A multi-word expression scored as one unit.
+#[derive(Serialize)]
+struct PhraseSpan {
+    kind: &'static str,        // "phrase" or "phrasal_verb"
+    phrase_id: Option<usize>, // Index into the dictionary's phrase list
+    text: String,
+    level: String,
+    tokens: Vec<usize>, // Indices into `details`
+    offsets: Offsets,   // First to last covered token
+}
+
+/// A finite verb group ("has not been finished") and its tense, aspect and voice.
+#[derive(Serialize)]
+struct VerbGroupSpan {
+    sentence: usize, // Index into `AnalysisResult.sentences`
+    text: String,
+    lemma: String,   // Main verb
+    tense: Tense,
+    aspect: Aspect,
+    voice: Voice,
+    modal: Option<String>,
+    negated: bool,
+    description: String, // e.g. "past perfect, passive, negated"
+    tokens: Vec<usize>,  // The verbs; indices into `details`
+    offsets: Offsets,
+}
+
+#[derive(Serialize)]
+struct SentenceSpan {
+    offsets: Offsets,
+    token_start: usize, // `details[token_start..token_end]` are the sentence's words
+    token_end: usize,
 }
 
 #[wasm_bindgen]
 pub fn analyze(text: &str) -> JsValue {
     set_panic_hook();
+    serde_wasm_bindgen::to_value(&analyze_text(text, TaggerMode::default())).unwrap()
+}
+
+/// `analyze` with the POS tagger chosen by name: "hmm" (the default of
+/// `analyze`), "rules" or "rules-base". Any other name throws rather than
+/// quietly running the default, so a typo like "rule" is caught.
+#[wasm_bindgen]
+pub fn analyze_with_tagger(text: &str, tagger: &str) -> Result<JsValue, String> {
+    set_panic_hook();
+    let mode = TaggerMode::from_name(tagger)
+        .ok_or_else(|| format!("unknown tagger {:?}: expected \"hmm\", \"rules\" or \"rules-base\"", tagger))?;
+    Ok(serde_wasm_bindgen::to_value(&analyze_text(text, mode)).unwrap())
+}
+
+/// Shallow parse of `text`: NP/VP/PP chunks grouped into main and
+/// subordinate clauses, as a JSON tree (see parser.rs).
+#[wasm_bindgen]
+pub fn parse_sentence(text: &str) -> JsValue {
+    set_panic_hook();
+    serde_wasm_bindgen::to_value(&parser::parse_text(text, TaggerMode::default())).unwrap()
+}
 
-    let sentences_text: Vec<&str> = text.split(|c| c == '.' || c == '!' || c == '?')
-                                        .filter(|s| !s.trim().is_empty())
+fn analyze_text(text: &str, mode: TaggerMode) -> AnalysisResult {
+    // Curly apostrophes, dashes, ligatures etc. -> canonical ASCII.
+    // Offsets in the result point into the original text.
+    let original = text;
+    let normalized = normalize(original);
+    let text = normalized.text.as_str();
+    let utf16 = Utf16Index::new(original);
+    let to_offsets = |range: std::ops::Range<usize>| utf16.offsets(normalized.original_range(range));
+
+    let sentence_ranges = split_sentences(text);
+    let sentences_text: Vec<&str> = sentence_ranges.iter()
+                                        .map(|span| &text[span.clone()])
                                         .collect();
     
     let mut all_sentences_tokens = Vec::new();
@@ -61,40 +168,149 @@ pub fn analyze(text: &str) -> JsValue {
     let mut scored_items = 0.0;
     let mut word_count = 0;
 
-    let en_stemmer = Stemmer::create(Algorithm::English);
-    
-    for sent_text in &sentences_text {
-        // Smart Tokenization with contraction handling
+    for (sent_text, range) in sentences_text.iter().zip(&sentence_ranges) {
+        // Smart Tokenization with contraction handling; punctuation is kept
+        // as separate tokens but not counted as words
         let tokens = tokenize_sentence(sent_text);
-        word_count += tokens.len();
-        
-        // Tagging
-        let tagged = tag_sentence(&tokens);
+
+        // Tagging (also resolves 's/'d, which may turn into words)
+        let mut tagged = tag_sentence(&tokens, mode);
+        word_count += tagged.iter().filter(|t| t.is_countable()).count();
+        for token in &mut tagged {
+            token.span = token.span.start + range.start..token.span.end + range.start;
+        }
         all_sentences_tokens.push(tagged);
     }
 
-    // Phrase Matching (Aho-Corasick)
-    let matches = DICT.phrase_matcher.find_iter(text);
-    for mat in matches {
-        let pattern_id = mat.pattern();
-        // Safe casting as per Aho-Corasick docs for 1.1 (usize is implied for pattern id)
-        if let Some((phrase, level)) = DICT.phrases.get(pattern_id.as_usize()) {
-            // Add phrase level to score logic
-            let score = level_to_score(level);
+    // Phrase Matching over the lemmatized token stream, per sentence.
+    // A phrase is scored once, in place of the words it covers.
+    let mut phrase_spans = Vec::new();
+    let mut token_phrase: HashMap<usize, usize> = HashMap::new(); // details index -> span index
+    let mut word_offset = 0; // details index of the sentence's first word
+    let mut verb_groups = Vec::new();
+    let mut tenses = TenseDistribution::default();
+    for (s, sent) in all_sentences_tokens.iter().enumerate() {
+        // Sentence position -> details index (punctuation and possessive 's have no detail entry)
+        let detail_index: Vec<usize> = sent.iter()
+            .scan(word_offset, |next, t| {
+                let index = *next;
+                if t.is_countable() { *next += 1; }
+                Some(index)
+            })
+            .collect();
+        let forms: Vec<Vec<String>> = sent.iter().map(token_forms).collect();
+        for mat in DICT.phrase_matcher.find_iter(&forms) {
+            if let Some((phrase, level)) = DICT.phrases.get(mat.phrase_id) {
+                // Add phrase level to score logic
+                let score = level_to_score(level);
+                if score > 0.0 {
+                    total_level_score += score;
+                    scored_items += 1.0;
+                }
+                unique_lemmas.insert(phrase.to_lowercase());
+
+                let tokens: Vec<usize> = (mat.start..mat.end)
+                    .filter(|&i| sent[i].is_countable())
+                    .map(|i| detail_index[i])
+                    .collect();
+                for &t in &tokens {
+                    token_phrase.insert(t, phrase_spans.len());
+                }
+                phrase_spans.push(PhraseSpan {
+                    kind: "phrase",
+                    phrase_id: Some(mat.phrase_id),
+                    text: phrase.to_string(),
+                    level: format!("{:?}", level),
+                    tokens,
+                    offsets: to_offsets(sent[mat.start].span.start..sent[mat.end - 1].span.end),
+                });
+            }
+        }
+
+        // Phrasal verbs, including split ones ("turn the lights off");
+        // skip any that overlap a fixed phrase already found
+        for pv in detect_phrasal_verbs(sent, &forms) {
+            let tokens = vec![detail_index[pv.verb], detail_index[pv.particle]];
+            if tokens.iter().any(|t| token_phrase.contains_key(t)) {
+                continue;
+            }
+            let score = level_to_score(&pv.level);
             if score > 0.0 {
                 total_level_score += score;
                 scored_items += 1.0;
             }
-            unique_lemmas.insert(phrase.to_lowercase());
+            unique_lemmas.insert(pv.text.clone());
+
+            for &t in &tokens {
+                token_phrase.insert(t, phrase_spans.len());
+            }
+            phrase_spans.push(PhraseSpan {
+                kind: "phrasal_verb",
+                phrase_id: None,
+                text: pv.text,
+                level: format!("{:?}", pv.level),
+                tokens,
+                offsets: to_offsets(sent[pv.verb].span.start..sent[pv.particle].span.end),
+            });
+        }
+
+        // Finite verb groups with tense, aspect and voice
+        for group in chunk_verb_groups(sent) {
+            tenses.add(&group);
+            let span = sent[group.span.start].span.start..sent[group.span.end - 1].span.end;
+            verb_groups.push(VerbGroupSpan {
+                sentence: s,
+                text: text[span.clone()].to_string(),
+                description: group.description(),
+                tokens: group.verbs.iter().map(|&v| detail_index[v]).collect(),
+                lemma: group.lemma,
+                tense: group.tense,
+                aspect: group.aspect,
+                voice: group.voice,
+                modal: group.modal,
+                negated: group.negated,
+                offsets: to_offsets(span),
+            });
         }
+        word_offset += sent.iter().filter(|t| t.is_countable()).count();
     }
 
     // Process tokens for details and single word scores
-    for sent in &all_sentences_tokens {
-        for token in sent {
+    let mut sentence_spans = Vec::new();
+    for (sent, range) in all_sentences_tokens.iter().zip(&sentence_ranges) {
+        let token_start = details.len();
+        for (i, token) in sent.iter().enumerate() {
+            if !token.is_countable() { continue; }
+            let offsets = to_offsets(token.span.clone());
+            let possessive = sent.get(i + 1).is_some_and(|t| t.clitic == Some(Clitic::Possessive));
+            let clitic = token.clitic.map(|c| c.name())
+                .or(possessive.then_some(Clitic::Possessive.name()));
+            // Verb clitics keep their written form ("'s"); `lemma` gives the reading
+            let text = token.clitic.map_or_else(|| token.word.clone(), |c| c.surface().to_string());
             let mut level_str = "Unknown".to_string();
-            let mut lemma = token.word.to_lowercase();
-            let is_phrase = false; 
+            let mut lemma: String;
+            let phrase = token_phrase.get(&details.len()).copied();
+            let is_phrase = phrase.is_some();
+            let mut source = None;
+            let mut sense = None;
+
+            // Numbers, URLs etc. are reported but carry no vocabulary level
+            if token.kind.is_non_lexical() {
+                details.push(TokenDetail {
+                    text,
+                    lemma: token.word.clone(),
+                    pos: token.tag.clone(),
+                    level: "NonLexical".to_string(),
+                    kind: token.kind.name(),
+                    is_phrase,
+                    phrase,
+                    source,
+                    sense,
+                    clitic,
+                    offsets,
+                });
+                continue;
+            }
             
             // PRIORITY 1: Check if it's a common name (capitalized and in name database)
             // Names should not be counted as "Unknown" words
@@ -105,46 +321,52 @@ pub fn analyze(text: &str) -> JsValue {
                 // Don't add to scored_items - names don't affect CEFR level
                 unique_lemmas.insert(lemma.clone());
                 details.push(TokenDetail {
-                    text: token.word.clone(),
+                    text,
                     lemma,
                     pos: token.tag.clone(),
                     level: level_str,
+                    kind: token.kind.name(),
                     is_phrase,
+                    phrase,
+                    source,
+                    sense,
+                    clitic,
+                    offsets,
                 });
                 continue; // Skip dictionary lookup for names
             }
             
-            // PRIORITY 2: Dictionary lookup
-            let mut found_in_dict = false;
-            if let Some(entry) = DICT.lookup(&token.word, Some(&token.tag)) {
-                level_str = format!("{:?}", entry.level);
-                lemma = entry.lemma.clone(); // Use dictionary lemma
-                found_in_dict = true;
+            // PRIORITY 2: Dictionary lookup (lemmatizes inflected forms internally).
+            // Hyphenated compounds missing as a whole take their hardest part's level.
+            let found = match DICT.lookup(&token.word, Some(&token.tag)) {
+                Some(entry) => Some((entry, entry.lemma.to_string())),
+                None => DICT.lookup_compound(&token.word, Some(&token.tag)).and_then(|parts| {
+                    let lemma = match parts.as_slice() {
+                        [closed] => closed.lemma.to_string(),
+                        _ => token.word.to_lowercase(),
+                    };
+                    let hardest = parts.into_iter().max_by(|a, b| level_to_score(&a.level).total_cmp(&level_to_score(&b.level)))?;
+                    Some((hardest, lemma))
+                }),
+            };
+            if let Some((entry, entry_lemma)) = found {
+                // Polysemous words take the level of the sense the context supports
+                let chosen = select_sense(entry, sent, i);
+                let level = chosen.map_or(&entry.level, |s| &s.level);
+                sense = chosen.map(|s| s.gloss);
+                level_str = format!("{:?}", level);
+                lemma = entry_lemma; // Use dictionary lemma
+                source = Some(entry.source.name());
                 
-                let score = level_to_score(&entry.level);
-                if score > 0.0 {
+                // Words inside a matched phrase were already scored as part of it
+                let score = level_to_score(level);
+                if score > 0.0 && !is_phrase {
                     total_level_score += score;
                     scored_items += 1.0;
                 }
-            }
-            
-            // If not found in dictionary, try stemming
-            if !found_in_dict {
-                let stemmed = en_stemmer.stem(&token.word);
-                // Try looking up the stemmed version
-                if let Some(entry) = DICT.lookup(&stemmed, Some(&token.tag)) {
-                     level_str = format!("{:?}", entry.level);
-                     lemma = entry.lemma.clone();
-                     
-                     let score = level_to_score(&entry.level);
-                     if score > 0.0 {
-                        total_level_score += score;
-                        scored_items += 1.0;
-                     }
-                } else {
-                    // Just use the stem as the lemma if still nothing
-                    lemma = stemmed.to_string();
-                }
+            } else {
+                // Not in dictionary: still report a proper lemma rather than the surface form
+                lemma = lemmatize(&token.word, Some(&token.tag));
             }
 
             // PRIORITY 3: Heuristic for proper nouns not in name database
@@ -159,29 +381,43 @@ pub fn analyze(text: &str) -> JsValue {
             unique_lemmas.insert(lemma.clone());
 
             details.push(TokenDetail {
-                text: token.word.clone(),
+                text,
                 lemma,
                 pos: token.tag.clone(),
                 level: level_str,
+                kind: token.kind.name(),
                 is_phrase,
+                phrase,
+                source,
+                sense,
+                clitic,
+                offsets,
             });
         }
+        sentence_spans.push(SentenceSpan {
+            offsets: to_offsets(range.clone()),
+            token_start,
+            token_end: details.len(),
+        });
     }
 
     // Metrics
-    let syntax_metrics = SyntacticAnalyzer::analyze(text, &all_sentences_tokens);
+    let syntax_metrics = SyntacticAnalyzer::analyze(text, &all_sentences_tokens, to_offsets);
     let discourse_metrics = DiscourseAnalyzer::analyze(&all_sentences_tokens);
+    let grammar_metrics = GrammarAnalyzer::analyze(&all_sentences_tokens, to_offsets);
 
     // Final CEFR Calculation (Heuristic)
     let avg_score = if scored_items > 0.0 { total_level_score / scored_items } else { 0.0 };
     // Adjust based on syntax (e.g., complicate syntax -> higher level)
     let adjusted_score = avg_score 
         + (syntax_metrics.clause_density * WEIGHT_CLAUSE_DENSITY) 
-        + (discourse_metrics.connective_sophistication * WEIGHT_CONNECTIVE_SOPHISTICATION);
+        + (discourse_metrics.connective_sophistication * WEIGHT_CONNECTIVE_SOPHISTICATION)
+        // Grammar structures above the vocabulary's level pull the text up
+        + ((grammar_metrics.score - avg_score).max(0.0) * WEIGHT_GRAMMAR_LEVEL);
     
     let final_level = score_to_level(adjusted_score);
 
-    let result = AnalysisResult {
+    AnalysisResult {
         cefr_level: final_level,
         lexical_score: avg_score,
         adjusted_score,
@@ -192,11 +428,14 @@ pub fn analyze(text: &str) -> JsValue {
             avg_sentence_length: if !sentences_text.is_empty() { word_count as f64 / sentences_text.len() as f64 } else { 0.0 },
             syntax: syntax_metrics,
             discourse: discourse_metrics,
+            grammar: grammar_metrics,
+            tenses,
         },
         details,
-    };
-
-    serde_wasm_bindgen::to_value(&result).unwrap()
+        phrases: phrase_spans,
+        verb_groups,
+        sentences: sentence_spans,
+    }
 }
 
 fn level_to_score(l: &CEFRLevel) -> f64 {
@@ -225,70 +464,124 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-fn tokenize_sentence(text: &str) -> Vec<String> {
-    let mut tokens = Vec::new();
-    // Split by whitespace first
-    for raw_word in text.split_whitespace() {
-        // Remove surrounding punctuation but keep internal apostrophes for now
-        let clean = raw_word.trim_matches(|c: char| !c.is_alphanumeric());
-        if clean.is_empty() { continue; }
-        
-        // Handle contractions
-        let expanded = expand_contraction(clean);
-        tokens.extend(expanded);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(text: &str) -> AnalysisResult {
+        analyze_text(text, TaggerMode::default())
     }
-    tokens
-}
 
-fn expand_contraction(word: &str) -> Vec<String> {
-    let lower = word.to_lowercase();
-    match lower.as_str() {
-        "don't" => vec!["do".to_string(), "not".to_string()],
-        "doesn't" => vec!["does".to_string(), "not".to_string()],
-        "didn't" => vec!["did".to_string(), "not".to_string()],
-        "can't" => vec!["can".to_string(), "not".to_string()],
-        "cannot" => vec!["can".to_string(), "not".to_string()],
-        "won't" => vec!["will".to_string(), "not".to_string()],
-        "shan't" => vec!["shall".to_string(), "not".to_string()],
-        "isn't" => vec!["is".to_string(), "not".to_string()],
-        "aren't" => vec!["are".to_string(), "not".to_string()],
-        "wasn't" => vec!["was".to_string(), "not".to_string()],
-        "weren't" => vec!["were".to_string(), "not".to_string()],
-        "couldn't" => vec!["could".to_string(), "not".to_string()],
-        "shouldn't" => vec!["should".to_string(), "not".to_string()],
-        "wouldn't" => vec!["would".to_string(), "not".to_string()],
-        "haven't" => vec!["have".to_string(), "not".to_string()],
-        "hasn't" => vec!["has".to_string(), "not".to_string()],
-        "hadn't" => vec!["had".to_string(), "not".to_string()],
-        
-        // Pronoun + be/have/will
-        // 'm -> am
-        // 're -> are
-        // 've -> have
-        // 'll -> will
-        s if s.ends_with("'m") && s.len() > 2 => {
-            vec![word[..word.len()-2].to_string(), "am".to_string()]
-        },
-        s if s.ends_with("'re") && s.len() > 3 => {
-            vec![word[..word.len()-3].to_string(), "are".to_string()]
-        },
-        s if s.ends_with("'ve") && s.len() > 3 => {
-            vec![word[..word.len()-3].to_string(), "have".to_string()]
-        },
-        s if s.ends_with("'ll") && s.len() > 3 => {
-            vec![word[..word.len()-3].to_string(), "will".to_string()]
-        },
-        // 's is ambiguous: is/has/possession. 
-        // For simplicity, we splits if it's likely "it's", "he's" etc
-        "it's" => vec!["it".to_string(), "is".to_string()],
-        "he's" => vec!["he".to_string(), "is".to_string()], 
-        "she's" => vec!["she".to_string(), "is".to_string()],
-        "that's" => vec!["that".to_string(), "is".to_string()],
-        "let's" => vec!["let".to_string(), "us".to_string()], 
-        
-        // 'd is ambiguous: would/had. Default to would for now? Or better leave it?
-        // Let's leave 'd and 's attached for non-pronouns to avoid breaking possessives.
-        
-        _ => vec![word.to_string()],
+    #[test]
+    fn an_unknown_tagger_name_is_an_error() {
+        let err = analyze_with_tagger("It works.", "rule").unwrap_err();
+        assert!(err.contains("\"rule\""), "{}", err);
+    }
+
+    #[test]
+    fn phrase_spans_mark_their_tokens() {
+        let result = analyze("We stayed home because of the rain.");
+        assert_eq!(result.phrases.len(), 1);
+        let span = &result.phrases[0];
+        assert_eq!((span.kind, span.text.as_str(), span.tokens.as_slice()), ("phrase", "because of", &[3, 4][..]));
+        assert_eq!(span.phrase_id.map(|id| DICT.phrases[id].0), Some("because of"));
+        assert!(result.details[3].is_phrase && result.details[4].is_phrase);
+        assert_eq!(result.details[3].phrase, Some(0));
+        assert!(!result.details[2].is_phrase && result.details[2].phrase.is_none());
+    }
+
+    #[test]
+    fn a_phrase_is_scored_once_in_place_of_its_words() {
+        let level = DICT.phrases.iter().find(|(p, _)| *p == "because of").map(|(_, l)| level_to_score(l)).unwrap();
+        let result = analyze("Because of");
+        assert_eq!(result.phrases.len(), 1);
+        assert_eq!(result.lexical_score, level);
+    }
+
+    #[test]
+    fn split_phrasal_verbs_are_reported_as_phrase_spans() {
+        let result = analyze("Please turn the lights off.");
+        let span = result.phrases.iter().find(|p| p.kind == "phrasal_verb").expect("no phrasal verb");
+        assert_eq!((span.text.as_str(), span.level.as_str(), span.tokens.as_slice()), ("turn off", "A2", &[1, 4][..]));
+        assert_eq!(span.phrase_id, None);
+        assert!(result.details[1].is_phrase && result.details[4].is_phrase && !result.details[2].is_phrase);
+    }
+
+    #[test]
+    fn punctuation_is_not_counted_as_words() {
+        let result = analyze("Well, we stayed; it rained!");
+        assert_eq!(result.metrics.word_count, 5);
+        assert!(result.details.iter().all(|d| d.text.chars().any(char::is_alphanumeric)));
+    }
+
+    #[test]
+    fn curly_apostrophes_split_contractions() {
+        let result = analyze("They don\u{2019}t know.");
+        let texts: Vec<&str> = result.details.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, vec!["They", "do", "not", "know"]);
+        assert_eq!(result.details[2].level, "A1");
+        // Offsets point into the original text, curly apostrophe included
+        assert_eq!((result.details[2].offsets.start, result.details[2].offsets.end), (7, 12));
+    }
+
+    #[test]
+    fn possessors_keep_their_lemma_and_clitics_are_reported() {
+        let result = analyze("The dog's bowl is where's the cat.");
+        let dog = &result.details[1];
+        assert_eq!((dog.text.as_str(), dog.lemma.as_str(), dog.clitic), ("dog", "dog", Some("possessive")));
+        assert!(result.details.iter().all(|d| d.text != "'s" || d.clitic == Some("is")));
+        let verb = result.details.iter().find(|d| d.text == "'s").unwrap();
+        assert_eq!((verb.pos.as_str(), verb.clitic), ("VBZ", Some("is")));
+    }
+
+    #[test]
+    fn tokens_phrases_and_sentences_carry_offsets() {
+        let text = "Caf\u{e9} \u{1F600} closed. We left because of rain.";
+        let result = analyze(text);
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        let slice16 = |o: &Offsets| String::from_utf16(&utf16[o.start_utf16..o.end_utf16]).unwrap();
+        for detail in &result.details {
+            assert_eq!(&text[detail.offsets.start..detail.offsets.end], slice16(&detail.offsets));
+        }
+        let closed = result.details.iter().find(|d| d.text == "closed").unwrap();
+        assert_eq!(slice16(&closed.offsets), "closed");
+        assert_eq!((closed.offsets.start, closed.offsets.start_utf16), (11, 8));
+
+        let phrase = &result.phrases[0];
+        assert_eq!(&text[phrase.offsets.start..phrase.offsets.end], "because of");
+        assert_eq!(result.sentences.len(), 2);
+        assert_eq!(slice16(&result.sentences[1].offsets), "We left because of rain.");
+        assert_eq!((result.sentences[1].token_start, result.sentences[1].token_end), (2, 7));
+    }
+
+    #[test]
+    fn non_lexical_tokens_are_counted_but_not_scored() {
+        let result = analyze("In 1990 I paid $3.50 on the 3rd.");
+        let levels: Vec<(&str, &str, &str)> = result.details.iter().map(|d| (d.text.as_str(), d.kind, d.level.as_str())).collect();
+        assert!(levels.contains(&("1990", "year", "NonLexical")));
+        assert!(levels.contains(&("$3.50", "currency", "NonLexical")));
+        assert!(levels.contains(&("3rd", "ordinal", "NonLexical")));
+        assert_eq!(result.metrics.word_count, 8);
+        assert!(result.details.iter().all(|d| d.level != "Unknown"));
+    }
+
+    #[test]
+    fn hyphenated_compounds_fall_back_to_their_hardest_part() {
+        let result = analyze("A well-known e-mail arrived.");
+        let email = result.details.iter().find(|d| d.text == "e-mail").unwrap();
+        assert_eq!(email.lemma, "email");
+        let compound = result.details.iter().find(|d| d.text == "well-known").unwrap();
+        assert_ne!(compound.level, "Unknown");
+        let parts: Vec<&str> = DICT.lookup_compound("sun-lotion-xq", None).map(|p| p.iter().map(|e| e.lemma).collect()).unwrap_or_default();
+        assert!(parts.is_empty());
+        let levels = DICT.lookup_compound("blue-green", Some("JJ")).unwrap();
+        assert_eq!(levels.len(), 2);
+    }
+
+    #[test]
+    fn passives_are_found_from_vbn_tags() {
+        assert_eq!(analyze("The car was sold. They sold the car.").metrics.syntax.passive_ratio, 0.5);
+        assert_eq!(analyze("The letter has been written.").metrics.syntax.passive_ratio, 1.0);
+        assert_eq!(analyze("She was tired and has left.").metrics.syntax.passive_ratio, 0.0);
     }
 }
@@ -0,0 +1,131 @@
+use std::ops::Range;
+
+/// Text after typographic normalization, with a map back to the original.
+pub struct Normalized {
+    pub text: String,
+    /// Runs of the normalized text in order: (normalized start, original start,
+    /// whether the run is a replacement rather than copied verbatim)
+    segments: Vec<(usize, usize, bool)>,
+    original_len: usize,
+}
+
+/// Canonical form of a typographic character, or `None` to keep it as is.
+fn canonical(c: char) -> Option<&'static str> {
+    Some(match c {
+        // Apostrophes and single quotes
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{02BC}' | '\u{2032}' | '\u{FF07}' => "'",
+        // Double quotes
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}' | '\u{00BB}' | '\u{FF02}' => "\"",
+        // Hyphens, en dash and minus join words and ranges; em dashes separate clauses
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2212}' => "-",
+        '\u{2014}' | '\u{2015}' => "--",
+        '\u{2026}' => "...",
+        // Non-breaking and narrow spaces
+        '\u{00A0}' | '\u{2002}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => " ",
+        // Invisible characters: soft hyphen, zero-width space/joiners, BOM
+        '\u{00AD}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => "",
+        // Latin ligatures
+        '\u{FB00}' => "ff",
+        '\u{FB01}' => "fi",
+        '\u{FB02}' => "fl",
+        '\u{FB03}' => "ffi",
+        '\u{FB04}' => "ffl",
+        '\u{FB05}' | '\u{FB06}' => "st",
+        _ => return None,
+    })
+}
+
+/// Map typographic quotes, apostrophes, dashes, special spaces and ligatures
+/// to plain ASCII so the tokenizer sees "don't", not "don\u{2019}t".
+pub fn normalize(original: &str) -> Normalized {
+    let mut text = String::with_capacity(original.len());
+    let mut segments = Vec::new();
+    let mut in_verbatim = false;
+
+    for (pos, c) in original.char_indices() {
+        match canonical(c) {
+            Some(replacement) => {
+                segments.push((text.len(), pos, true));
+                text.push_str(replacement);
+                in_verbatim = false;
+            }
+            None => {
+                if !in_verbatim {
+                    segments.push((text.len(), pos, false));
+                    in_verbatim = true;
+                }
+                text.push(c);
+            }
+        }
+    }
+
+    Normalized { text, segments, original_len: original.len() }
+}
+
+impl Normalized {
+    /// Byte offset in the original text of normalized byte offset `pos`.
+    /// Offsets inside a replacement map to the start of the replaced character.
+    pub fn original_offset(&self, pos: usize) -> usize {
+        if pos >= self.text.len() {
+            return self.original_len;
+        }
+        let index = self.segment_at(pos);
+        let (start, original_start, replaced) = self.segments[index];
+        if replaced {
+            original_start
+        } else {
+            original_start + (pos - start)
+        }
+    }
+
+    /// Original byte range of a normalized range. A range ending inside a
+    /// replacement ("f" of "\u{FB01}" -> "fi") extends over the whole character.
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.original_offset(range.start);
+        let mut end = self.original_offset(range.end);
+        if range.end < self.text.len() {
+            let index = self.segment_at(range.end);
+            let (seg_start, _, replaced) = self.segments[index];
+            if replaced && seg_start < range.end {
+                end = self.segments.get(index + 1).map_or(self.original_len, |&(_, o, _)| o);
+            }
+        }
+        start..end.max(start)
+    }
+
+    fn segment_at(&self, pos: usize) -> usize {
+        self.segments.partition_point(|&(start, _, _)| start <= pos) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typographic_characters_become_ascii() {
+        let n = normalize("\u{201C}Don\u{2019}t\u{201D} \u{2014} the \u{FB01}rst\u{00A0}9\u{2013}5 job\u{2026}");
+        assert_eq!(n.text, "\"Don't\" -- the first 9-5 job...");
+        assert_eq!(normalize("soft\u{00AD}ware").text, "software");
+    }
+
+    #[test]
+    fn offsets_map_back_to_the_original() {
+        let original = "don\u{2019}t stop";
+        let n = normalize(original);
+        assert_eq!(n.text, "don't stop");
+        // "stop" moved two bytes left: the apostrophe shrank from 3 bytes to 1
+        assert_eq!(n.original_range(6..10), 8..12);
+        assert_eq!(&original[n.original_range(0..5)], "don\u{2019}t");
+        assert_eq!(n.original_offset(n.text.len()), original.len());
+    }
+
+    #[test]
+    fn ranges_ending_inside_an_expansion_cover_the_whole_character() {
+        let original = "\u{FB01}ne";
+        let n = normalize(original);
+        assert_eq!(n.text, "fine");
+        assert_eq!(n.original_range(0..1), 0..3);
+        assert_eq!(n.original_range(1..4), 0..5);
+    }
+}
@@ -0,0 +1,72 @@
+use std::ops::Range;
+use serde::Serialize;
+
+/// Position of a token, phrase or sentence in the analyzed text, in UTF-8
+/// bytes (Rust) and UTF-16 code units (JavaScript string indices).
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Offsets {
+    pub start: usize,
+    pub end: usize,
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+}
+
+/// Converts byte offsets of one text to UTF-16 offsets.
+pub struct Utf16Index {
+    /// (byte offset just past a non-ASCII char, bytes minus UTF-16 units up to there)
+    checkpoints: Vec<(usize, usize)>,
+}
+
+impl Utf16Index {
+    pub fn new(text: &str) -> Self {
+        let mut checkpoints = Vec::new();
+        let mut surplus = 0;
+        for (pos, c) in text.char_indices() {
+            if !c.is_ascii() {
+                surplus += c.len_utf8() - c.len_utf16();
+                checkpoints.push((pos + c.len_utf8(), surplus));
+            }
+        }
+        Utf16Index { checkpoints }
+    }
+
+    /// UTF-16 offset of byte offset `pos` (a char boundary).
+    pub fn utf16(&self, pos: usize) -> usize {
+        let index = self.checkpoints.partition_point(|&(end, _)| end <= pos);
+        let surplus = index.checked_sub(1).map_or(0, |i| self.checkpoints[i].1);
+        pos - surplus
+    }
+
+    pub fn offsets(&self, range: Range<usize>) -> Offsets {
+        Offsets {
+            start: range.start,
+            end: range.end,
+            start_utf16: self.utf16(range.start),
+            end_utf16: self.utf16(range.end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_offsets_are_unchanged() {
+        let index = Utf16Index::new("plain text");
+        assert_eq!(index.offsets(6..10), Offsets { start: 6, end: 10, start_utf16: 6, end_utf16: 10 });
+    }
+
+    #[test]
+    fn multibyte_characters_shift_utf16_offsets() {
+        // "é" is 2 bytes / 1 unit, "中" 3 bytes / 1 unit, "😀" 4 bytes / 2 units
+        let text = "é中😀 ok";
+        let index = Utf16Index::new(text);
+        let ok = text.find("ok").unwrap();
+        assert_eq!(ok, 10);
+        assert_eq!(index.utf16(ok), 5);
+        assert_eq!(index.utf16(2), 1);
+        assert_eq!(index.utf16(5), 2);
+        assert_eq!(index.utf16(0), 0);
+    }
+}
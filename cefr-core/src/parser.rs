@@ -0,0 +1,788 @@
+//! Shallow parse: NP/VP/PP/ADJP/ADVP chunks over the tagged tokens, grouped
+//! into main and subordinate (relative, adverbial, complement) clauses.
+//!
+//! Chunking is a left-to-right pass; finite VPs are the verb groups of
+//! verb_groups.rs. Clauses are a stack: an opener ("who", "because", "that")
+//! pushes a clause, which closes when a second finite verb arrives for the
+//! clause above it, at a comma once it has its verb, or at a coordinator
+//! that starts a new subject and verb. The syntax metrics (syntax.rs) are
+//! read off the same clause tree.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use serde::Serialize;
+use crate::lemmatizer::lemmatize;
+use crate::normalize::normalize;
+use crate::offsets::{Offsets, Utf16Index};
+use crate::pos::{tag_sentence, TaggedToken, TaggerMode};
+use crate::segmenter::split_sentences;
+use crate::tokenizer::tokenize_sentence;
+use crate::verb_groups::{chunk_verb_groups, lower};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChunkKind {
+    Np,
+    Vp,
+    Pp,
+    Adjp,
+    Advp,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClauseKind {
+    Main,
+    Relative,
+    Adverbial,
+    Complement,
+}
+
+impl ClauseKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClauseKind::Main => "main",
+            ClauseKind::Relative => "relative",
+            ClauseKind::Adverbial => "adverbial",
+            ClauseKind::Complement => "complement",
+        }
+    }
+}
+
+/// Parse of a text: its tokens and the main clauses built over them.
+#[derive(Serialize, Debug)]
+pub struct ParseTree {
+    pub tokens: Vec<ParsedToken>,
+    /// Main clauses in order; coordinated and separate sentences each get one
+    pub clauses: Vec<ClauseNode>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParsedToken {
+    pub text: String,
+    pub tag: String,
+    pub offsets: Offsets,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Node {
+    Clause(ClauseNode),
+    Chunk(ChunkNode),
+    Token(TokenNode),
+}
+
+#[derive(Serialize, Debug)]
+pub struct ClauseNode {
+    pub kind: ClauseKind,
+    /// Index of the subordinator or relative word; `None` for main clauses,
+    /// zero relatives ("the book I read") and zero complements ("I think he
+    /// is right")
+    pub opener: Option<usize>,
+    /// Token range `start..end` into `ParseTree.tokens`
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub offsets: Offsets,
+    pub children: Vec<Node>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChunkNode {
+    pub kind: ChunkKind,
+    pub start: usize,
+    pub end: usize,
+    /// Head word's token index: the noun, main verb, preposition, ...
+    pub head: usize,
+    pub text: String,
+    pub offsets: Offsets,
+    /// A PP's object (NP, or VP for "by leaving")
+    pub children: Vec<ChunkNode>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TokenNode {
+    pub index: usize,
+}
+
+/// Tag and parse `text` (one or more sentences).
+pub fn parse_text(text: &str, mode: TaggerMode) -> ParseTree {
+    let original = text;
+    let normalized = normalize(original);
+    let text = normalized.text.as_str();
+    let utf16 = Utf16Index::new(original);
+    let to_offsets = |range: Range<usize>| utf16.offsets(normalized.original_range(range));
+
+    let mut tokens = Vec::new();
+    let mut clauses = Vec::new();
+    for range in split_sentences(text) {
+        let mut sent = tag_sentence(&tokenize_sentence(&text[range.clone()]), mode);
+        for token in &mut sent {
+            token.span = token.span.start + range.start..token.span.end + range.start;
+        }
+        let build = Builder { sent: &sent, base: tokens.len(), text, to_offsets: &to_offsets };
+        clauses.extend(build.clauses(&chunk(&sent)));
+        tokens.extend(sent.iter().map(|t| ParsedToken {
+            text: text[t.span.clone()].to_string(),
+            tag: t.tag.clone(),
+            offsets: to_offsets(t.span.clone()),
+        }));
+    }
+    ParseTree { tokens, clauses }
+}
+
+/// Main clauses of one tagged sentence, with token indices into `sent`.
+/// `text` is what the tokens' spans index and `to_offsets` maps its byte
+/// ranges, as in `parse_text`.
+pub(crate) fn parse_sentence(sent: &[TaggedToken], text: &str, to_offsets: &impl Fn(Range<usize>) -> Offsets) -> Vec<ClauseNode> {
+    Builder { sent, base: 0, text, to_offsets }.clauses(&chunk(sent))
+}
+
+// ==================== CHUNKING ====================
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    kind: ChunkKind,
+    span: Range<usize>,
+    head: usize,
+    /// Finite VP (a verb group)
+    finite: bool,
+    object: Option<Box<Chunk>>,
+}
+
+#[derive(Debug, Clone)]
+enum Unit {
+    Chunk(Chunk),
+    /// A word that opens a subordinate clause
+    Opener(usize, ClauseKind),
+    Token(usize),
+}
+
+impl Unit {
+    fn chunk(&self) -> Option<&Chunk> {
+        match self {
+            Unit::Chunk(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn is_finite_vp(&self) -> bool {
+        self.chunk().is_some_and(|c| c.finite)
+    }
+}
+
+fn is_noun_like(tag: &str) -> bool {
+    tag.starts_with("NN") || tag == "CD"
+}
+
+fn is_np_start(tag: &str) -> bool {
+    matches!(tag, "DT" | "PDT" | "PRP$" | "WP$" | "CD" | "PRP" | "EX") || tag.starts_with("NN") || tag.starts_with("JJ")
+}
+
+/// Verbs whose wh-clause is an object ("know where", "ask when"), not an adverbial
+const WH_COMPLEMENT_VERBS: &[&str] = &[
+    "know", "wonder", "ask", "tell", "remember", "forget", "see", "decide", "explain", "understand",
+    "show", "learn", "find", "discover", "realize", "realise", "say", "guess", "imagine", "check",
+];
+
+fn chunk(sent: &[TaggedToken]) -> Vec<Unit> {
+    let groups: HashMap<usize, Range<usize>> = chunk_verb_groups(sent).into_iter().map(|g| (g.span.start, g.span)).collect();
+    let group_starts: HashSet<usize> = groups.keys().copied().collect();
+    let mut units: Vec<Unit> = Vec::new();
+    let mut i = 0;
+    while i < sent.len() {
+        let unit = if let Some(span) = groups.get(&i) {
+            let span = with_particle(sent, span.clone());
+            let head = (span.start..span.end).rev().find(|&k| sent[k].tag.starts_with("VB") || sent[k].tag == "MD").unwrap_or(span.start);
+            Unit::Chunk(Chunk { kind: ChunkKind::Vp, span, head, finite: true, object: None })
+        } else if let Some(kind) = clause_opener(sent, i, &group_starts) {
+            Unit::Opener(i, kind)
+        } else if let Some(chunk) = phrase_at(sent, i, &group_starts) {
+            Unit::Chunk(chunk)
+        } else {
+            Unit::Token(i)
+        };
+        i = match &unit {
+            Unit::Chunk(c) => c.span.end,
+            _ => i + 1,
+        };
+        units.push(unit);
+    }
+    units
+}
+
+fn is_punct_unit(sent: &[TaggedToken], unit: &Unit) -> bool {
+    matches!(unit, Unit::Token(i) if !sent[*i].is_word())
+}
+
+/// A verb's particle belongs to its VP ("gave up")
+fn with_particle(sent: &[TaggedToken], span: Range<usize>) -> Range<usize> {
+    if sent.get(span.end).is_some_and(|t| t.tag == "RP") { span.start..span.end + 1 } else { span }
+}
+
+/// NP, PP, non-finite VP, ADJP or ADVP starting at `i`.
+fn phrase_at(sent: &[TaggedToken], i: usize, group_starts: &HashSet<usize>) -> Option<Chunk> {
+    let token = &sent[i];
+    if !token.is_word() {
+        return None;
+    }
+    let tag = token.tag.as_str();
+
+    // Infinitive "to (not) leave", "to be done"
+    if tag == "TO" {
+        let v = (i + 1..sent.len()).find(|&k| !sent[k].tag.starts_with("RB"))?;
+        if sent[v].tag == "VB" {
+            return Some(verb_chunk(sent, i, v));
+        }
+    }
+    // Preposition and its object; "so" is IN only as a conjunction
+    if tag == "IN" && lower(token) != "so" || tag == "TO" {
+        let object = noun_phrase(sent, i + 1)
+            .or_else(|| sent.get(i + 1).filter(|t| t.tag == "VBG" && !group_starts.contains(&(i + 1))).map(|_| verb_chunk(sent, i + 1, i + 1)))?;
+        return Some(Chunk { kind: ChunkKind::Pp, span: i..object.span.end, head: i, finite: false, object: Some(Box::new(object)) });
+    }
+    if let Some(np) = noun_phrase(sent, i) {
+        return Some(np);
+    }
+    if tag.starts_with("VB") {
+        return Some(verb_chunk(sent, i, i));
+    }
+    // Adverbs before an adjective belong to the ADJP ("very tired")
+    let adverbs_end = (i..sent.len()).find(|&k| !sent[k].tag.starts_with("RB")).unwrap_or(sent.len());
+    if sent.get(adverbs_end).is_some_and(|t| t.tag.starts_with("JJ")) {
+        let end = (adverbs_end..sent.len()).find(|&k| !sent[k].tag.starts_with("JJ")).unwrap_or(sent.len());
+        return Some(Chunk { kind: ChunkKind::Adjp, span: i..end, head: end - 1, finite: false, object: None });
+    }
+    if tag.starts_with("RB") {
+        return Some(Chunk { kind: ChunkKind::Advp, span: i..adverbs_end, head: adverbs_end - 1, finite: false, object: None });
+    }
+    None
+}
+
+/// A non-finite verb run from `start`, whose first verb is at `first`
+/// ("to have been told", "leaving", "gave up"). A finite verb after it
+/// starts its own group ("standing there | is").
+fn verb_chunk(sent: &[TaggedToken], start: usize, first: usize) -> Chunk {
+    let non_finite = |k: usize| sent.get(k).is_some_and(|t| matches!(t.tag.as_str(), "VB" | "VBG" | "VBN"));
+    let end = (first..sent.len())
+        .find(|&k| !(k == first || non_finite(k) || sent[k].tag.starts_with("RB") && non_finite(k + 1)))
+        .unwrap_or(sent.len());
+    let head = (first..end).rev().find(|&k| sent[k].tag.starts_with("VB")).unwrap_or(first);
+    Chunk { kind: ChunkKind::Vp, span: with_particle(sent, start..end), head, finite: false, object: None }
+}
+
+/// Determiners, then modifiers, then nouns: "all the very old books",
+/// "John's new car", "she". Adjectives without a determiner or noun are an
+/// ADJP instead ("is happy").
+fn noun_phrase(sent: &[TaggedToken], start: usize) -> Option<Chunk> {
+    let first = sent.get(start).filter(|t| t.is_word() && is_np_start(&t.tag))?;
+    if matches!(first.tag.as_str(), "PRP" | "EX") {
+        return Some(Chunk { kind: ChunkKind::Np, span: start..start + 1, head: start, finite: false, object: None });
+    }
+    let mut k = start;
+    let mut has_determiner = false;
+    let mut head = None;
+    while k < sent.len() && sent[k].is_word() || sent.get(k).is_some_and(|t| t.tag == "POS") {
+        let tag = sent[k].tag.as_str();
+        let next = sent.get(k + 1).map(|t| t.tag.as_str()).unwrap_or("");
+        let fits = match tag {
+            // Determiners only before any modifier or noun ("all the", not "the the")
+            "DT" | "PDT" | "PRP$" | "WP$" => head.is_none() && (k == start || has_determiner && sent[k - 1].tag == "PDT"),
+            // After a noun only more nouns or a possessive ("the children's toys")
+            _ if head.is_some() && sent[k - 1].tag != "POS" => is_noun_like(tag) || tag == "POS",
+            "POS" => head.is_some(),
+            _ if tag.starts_with("JJ") || is_noun_like(tag) => true,
+            // "very" in "a very old house", participles in "the broken window"
+            _ if tag.starts_with("RB") => k > start && (next.starts_with("JJ") || next.starts_with("RB")),
+            "VBN" | "VBG" => k > start && (is_noun_like(next) || next.starts_with("JJ")),
+            _ => false,
+        };
+        if !fits {
+            break;
+        }
+        has_determiner |= matches!(tag, "DT" | "PDT" | "PRP$" | "WP$");
+        if is_noun_like(tag) {
+            head = Some(k);
+        } else if tag == "POS" {
+            head = None;
+        }
+        k += 1;
+    }
+    if k == start {
+        return None;
+    }
+    // A bare determiner is a pronoun ("that is", "all of them"); bare adjectives are an ADJP
+    let head = match head {
+        Some(h) => h,
+        None if has_determiner => k - 1,
+        None => return None,
+    };
+    Some(Chunk { kind: ChunkKind::Np, span: start..k, head, finite: false, object: None })
+}
+
+// ==================== CLAUSE OPENERS ====================
+
+/// A finite verb follows `i`, with only a subject noun phrase between:
+/// "after (he) left", "since (the war) began". Prepositions take a plain
+/// object instead ("after lunch").
+fn clause_follows(sent: &[TaggedToken], i: usize, group_starts: &HashSet<usize>) -> bool {
+    match noun_phrase(sent, i + 1) {
+        Some(np) => group_starts.contains(&np.span.end),
+        None => false,
+    }
+}
+
+/// A clause at `i` that is the sentence's subject: its own finite verb, then
+/// the main verb with no comma between ("Where he lives (is) a secret"),
+/// where an adverbial clause ends at a comma ("Where he goes, trouble follows").
+fn subject_clause_follows(sent: &[TaggedToken], i: usize, group_starts: &HashSet<usize>) -> bool {
+    if !clause_follows(sent, i, group_starts) {
+        return false;
+    }
+    let mut verbs = (i + 1..sent.len()).filter(|k| group_starts.contains(k));
+    let (Some(_), Some(main)) = (verbs.next(), verbs.next()) else { return false };
+    !sent[i + 1..main].iter().any(|t| matches!(t.word.as_str(), "," | ";" | ":" | "--"))
+}
+
+/// An auxiliary and then its subject right after `i`: "when (did you)",
+/// "where (is the) station". The wh-word before it asks a question.
+fn inversion_follows(sent: &[TaggedToken], i: usize) -> bool {
+    let (Some(aux), Some(subject)) = (sent.get(i + 1), sent.get(i + 2)) else { return false };
+    let auxiliary = aux.tag == "MD"
+        || matches!(lower(aux).as_str(), "do" | "does" | "did" | "is" | "are" | "was" | "were" | "am" | "have" | "has" | "had");
+    auxiliary && subject.is_word() && matches!(subject.tag.as_str(), "PRP" | "DT" | "PRP$" | "EX" | "NNP" | "NNPS")
+}
+
+/// Words that may open an adverbial clause right after complementizer "that"
+const ADVERBIAL_SUBORDINATORS: &[&str] = &[
+    "if", "when", "because", "although", "though", "while", "once", "after", "before", "since", "unless", "as",
+];
+
+/// Verbs that take a complement clause without "that" ("I think he is right")
+pub(crate) const ZERO_THAT_VERBS: &[&str] = &[
+    "think", "believe", "know", "say", "hope", "guess", "feel", "suppose", "wish", "expect",
+    "realize", "realise", "hear", "mean", "reckon", "imagine", "notice", "doubt", "admit", "promise",
+];
+
+/// Nouns of time that "when" modifies as a relative ("the day when")
+const TIME_NOUNS: &[&str] = &[
+    "time", "day", "moment", "year", "week", "month", "period", "age", "era", "night", "morning",
+    "evening", "afternoon", "hour", "season", "occasion", "century", "decade", "summer", "winter",
+];
+
+/// Whether `sent[i]` opens a subordinate clause, and which kind, from the
+/// words around it:
+/// - "that": determiner before a noun ("that book") and pronoun ("that is
+///   true", "after that") are not openers; after "so"/"such" it opens a
+///   result clause, after a noun a relative, before a subject and verb a
+///   complement
+/// - "after", "before", "since", "until", "as": conjunctions only before a
+///   subject and finite verb, prepositions otherwise ("after lunch")
+/// - wh-words: relative after a noun, complement after a verb of knowing or
+///   asking ("wonder where"), interrogative (no clause) at the start of a
+///   question or before an inverted auxiliary ("When did you ...");
+///   a sentence-initial "what" before a subject and verb opens a nominal
+///   clause ("What she said was true"), typed as a complement, as do
+///   "where", "how", "why" and "whether" when the main verb follows with no
+///   comma ("Where he lives is a secret"); "the reason why" is a relative
+/// - nothing opens a clause at the end of a sentence ("I don't know when.")
+///
+/// `group_starts` are the first tokens of the sentence's finite verb groups.
+pub fn clause_opener(sent: &[TaggedToken], i: usize, group_starts: &HashSet<usize>) -> Option<ClauseKind> {
+    let token = &sent[i];
+    if !token.is_word() {
+        return None;
+    }
+    let word = lower(token);
+    let prev_index = (0..i).rev().find(|&k| sent[k].is_word());
+    let prev = prev_index.map(|k| &sent[k]);
+    let next = sent.get(i + 1).filter(|t| t.is_word());
+    next?;
+
+    // "those who", "the book that", "the man whose"
+    let after_noun = prev.is_some_and(|p| p.tag.starts_with("NN") || matches!(p.tag.as_str(), "CD" | "DT"));
+    // "knew that", "gave up what", "sure that"
+    let after_verb = prev.is_some_and(|p| p.tag.starts_with("VB") || p.tag.starts_with("JJ") || p.tag == "RP");
+    let verb = prev_index
+        .and_then(|k| if sent[k].tag == "RP" { k.checked_sub(1) } else { Some(k) })
+        .map(|k| &sent[k])
+        .filter(|t| t.tag.starts_with("VB"));
+    let wh_complement_verb = verb.is_some_and(|v| WH_COMPLEMENT_VERBS.contains(&lemmatize(&v.word, Some(&v.tag)).to_lowercase().as_str()));
+    let question = sent.iter().rev().find(|t| !t.is_word()).is_some_and(|t| t.word == "?");
+    let interrogative = (prev.is_none() && question) || inversion_follows(sent, i);
+
+    match word.as_str() {
+        "who" | "whom" | "whose" | "which" if interrogative => None,
+        "who" | "whom" | "whose" | "which" if after_noun => Some(ClauseKind::Relative),
+        "who" | "whom" | "whose" | "which" if after_verb => Some(ClauseKind::Complement),
+
+        // "said that if ...": a complement opening with an adverbial clause
+        "that" if after_verb && next.is_some_and(|t| ADVERBIAL_SUBORDINATORS.contains(&lower(t).as_str())) => Some(ClauseKind::Complement),
+        // Determiner ("that book") and pronoun ("That is true", "like that")
+        "that" if token.tag == "DT" && next.is_some_and(|t| t.tag.starts_with("NN") || t.tag.starts_with("JJ")) => None,
+        // "so cold that", "such a mess that"
+        "that" if (0..i).rev().filter(|&k| sent[k].is_word()).take(4).any(|k| matches!(lower(&sent[k]).as_str(), "so" | "such")) => {
+            Some(ClauseKind::Adverbial)
+        }
+        "that" if after_noun => Some(ClauseKind::Relative),
+        "that" if clause_follows(sent, i, group_starts) => Some(ClauseKind::Complement),
+
+        "where" | "how" | "why" | "whether" if prev.is_none() && !question && subject_clause_follows(sent, i, group_starts) => {
+            Some(ClauseKind::Complement)
+        }
+        "why" if prev.is_some_and(|p| lower(p) == "reason") => Some(ClauseKind::Relative),
+
+        "where" | "when" if interrogative => None,
+        "where" if after_noun => Some(ClauseKind::Relative),
+        "when" if prev.is_some_and(|p| TIME_NOUNS.contains(&lemmatize(&p.word, Some(&p.tag)).to_lowercase().as_str())) => {
+            Some(ClauseKind::Relative)
+        }
+        "where" | "when" if wh_complement_verb => Some(ClauseKind::Complement),
+        "where" | "when" => Some(ClauseKind::Adverbial),
+
+        "why" | "how" | "what" if wh_complement_verb && !inversion_follows(sent, i) => Some(ClauseKind::Complement),
+        "what" if prev.is_none() && !question && clause_follows(sent, i, group_starts) => Some(ClauseKind::Complement),
+        // "Whether you like it or not, ..."
+        "whether" if prev.is_none() => Some(ClauseKind::Adverbial),
+        "whether" => Some(ClauseKind::Complement),
+        "if" if wh_complement_verb => Some(ClauseKind::Complement),
+        "if" | "because" | "although" | "unless" | "whereas" => Some(ClauseKind::Adverbial),
+        "though" | "while" | "once" if token.tag == "IN" || clause_follows(sent, i, group_starts) => Some(ClauseKind::Adverbial),
+        "after" | "before" | "since" | "until" | "till" | "as" if clause_follows(sent, i, group_starts) => Some(ClauseKind::Adverbial),
+        _ => None,
+    }
+}
+
+/// Pronouns that can only or usually be a clause's subject
+pub(crate) const SUBJECT_PRONOUNS: &[&str] = &["i", "you", "he", "she", "it", "we", "they"];
+
+pub(crate) fn is_subject_pronoun(token: &TaggedToken) -> bool {
+    SUBJECT_PRONOUNS.contains(&lower(token).as_str())
+}
+
+/// "The book I read": a noun phrase with a determiner, then a subject
+/// pronoun and its verb, with no relative word. A sentence's subject may
+/// take a full noun phrase instead ("The man the police arrested was
+/// released") when a finite verb for it follows.
+fn is_zero_relative(sent: &[TaggedToken], units: &[Unit], u: usize) -> bool {
+    let Some(np) = u.checked_sub(1).and_then(|p| units[p].chunk()).filter(|c| c.kind == ChunkKind::Np) else { return false };
+    let Some(subject) = units[u].chunk().filter(|c| c.kind == ChunkKind::Np) else { return false };
+    if !matches!(sent[np.span.start].tag.as_str(), "DT" | "PDT" | "PRP$")
+        || np.span.end != subject.span.start
+        || !units.get(u + 1).is_some_and(Unit::is_finite_vp)
+    {
+        return false;
+    }
+    if subject.span.len() == 1 && is_subject_pronoun(&sent[subject.head]) {
+        return true;
+    }
+    let sentence_subject = !units[..u].iter().any(Unit::is_finite_vp);
+    sentence_subject
+        && matches!(sent[subject.span.start].tag.as_str(), "DT" | "PRP$")
+        && units[u + 2..].iter().any(Unit::is_finite_vp)
+}
+
+/// "The man standing there", "the book written by him": a participle right
+/// after a noun phrase opens a relative clause, which has its verb
+fn is_reduced_relative(sent: &[TaggedToken], prev: Option<&Unit>, chunk: &Chunk) -> bool {
+    chunk.kind == ChunkKind::Vp
+        && !chunk.finite
+        && matches!(sent[chunk.span.start].tag.as_str(), "VBG" | "VBN")
+        && prev.and_then(Unit::chunk).is_some_and(|c| c.kind == ChunkKind::Np && sent[c.head].tag.starts_with("NN"))
+}
+
+/// "I think he is right": a verb of saying or thinking, then a subject and
+/// its finite verb, with no "that"
+fn is_zero_complement(sent: &[TaggedToken], prev: Option<&Unit>, unit: &Unit, next: Option<&Unit>) -> bool {
+    let Some(verb) = prev.and_then(Unit::chunk).filter(|c| c.finite) else { return false };
+    let head = &sent[verb.head];
+    ZERO_THAT_VERBS.contains(&lemmatize(&head.word, Some(&head.tag)).to_lowercase().as_str())
+        && unit.chunk().is_some_and(|c| c.kind == ChunkKind::Np && matches!(sent[c.span.start].tag.as_str(), "PRP" | "DT" | "PRP$" | "EX" | "NNP"))
+        && next.is_some_and(Unit::is_finite_vp)
+}
+
+// ==================== CLAUSES ====================
+
+struct OpenClause {
+    kind: ClauseKind,
+    opener: Option<usize>,
+    has_verb: bool,
+    children: Vec<Node>,
+}
+
+impl OpenClause {
+    fn new(kind: ClauseKind, opener: Option<usize>) -> Self {
+        OpenClause { kind, opener, has_verb: false, children: Vec::new() }
+    }
+}
+
+struct Builder<'a, F: Fn(Range<usize>) -> Offsets> {
+    sent: &'a [TaggedToken],
+    /// Index of `sent[0]` in `ParseTree.tokens`
+    base: usize,
+    text: &'a str,
+    to_offsets: &'a F,
+}
+
+impl<F: Fn(Range<usize>) -> Offsets> Builder<'_, F> {
+    fn clauses(&self, units: &[Unit]) -> Vec<ClauseNode> {
+        let mut done = Vec::new();
+        let mut stack = vec![OpenClause::new(ClauseKind::Main, None)];
+
+        for (u, unit) in units.iter().enumerate() {
+            let prev = units[..u].iter().rev().find(|p| !is_punct_unit(self.sent, p));
+            let next = units.get(u + 1);
+            match unit {
+                Unit::Opener(i, kind) => {
+                    let mut clause = OpenClause::new(*kind, Some(self.base + i));
+                    clause.children.push(Node::Token(TokenNode { index: self.base + i }));
+                    stack.push(clause);
+                }
+                Unit::Chunk(chunk) if chunk.finite => {
+                    // A second finite verb belongs to the clause above, and so
+                    // does a subject pronoun just before it ("although he was
+                    // tired he came"), unless the clause is the sentence's
+                    // subject and the pronoun its object ("how she did it is")
+                    let mut subject = None;
+                    let subject_clause = stack.len() == 2 && stack[0].children.is_empty() && stack[1].kind == ClauseKind::Complement;
+                    if stack.len() > 1 && stack.last().is_some_and(|c| c.has_verb) && !subject_clause {
+                        let top = stack.last_mut().unwrap();
+                        if top.children.last().is_some_and(|n| self.is_subject_pronoun(n)) {
+                            subject = top.children.pop();
+                        }
+                    }
+                    while stack.len() > 1 && stack.last().is_some_and(|c| c.has_verb) {
+                        self.close(&mut stack);
+                    }
+                    let top = stack.last_mut().unwrap();
+                    top.children.extend(subject);
+                    top.children.push(Node::Chunk(self.chunk_node(chunk)));
+                    top.has_verb = true;
+                }
+                Unit::Chunk(chunk) => {
+                    if is_zero_relative(self.sent, units, u) {
+                        stack.push(OpenClause::new(ClauseKind::Relative, None));
+                    } else if is_zero_complement(self.sent, prev, unit, next) {
+                        stack.push(OpenClause::new(ClauseKind::Complement, None));
+                    } else if is_reduced_relative(self.sent, prev, chunk) {
+                        stack.push(OpenClause { has_verb: true, ..OpenClause::new(ClauseKind::Relative, None) });
+                    }
+                    stack.last_mut().unwrap().children.push(Node::Chunk(self.chunk_node(chunk)));
+                }
+                Unit::Token(i) => {
+                    let token = &self.sent[*i];
+                    let node = Node::Token(TokenNode { index: self.base + i });
+                    match token.word.as_str() {
+                        // PTB tags a clause-joining "so" IN ("It rained, so we stayed")
+                        _ if token.is_word() && (token.tag == "CC" || token.tag == "IN" && lower(token) == "so") && self.starts_clause(&units[u + 1..]) => {
+                            // "..., and she left": close everything and start a new main clause
+                            self.close_all(&mut stack, &mut done);
+                            stack[0].children.push(node);
+                        }
+                        "," | ":" | "--" if !token.is_word() => {
+                            while stack.len() > 1 && stack.last().is_some_and(|c| c.has_verb) {
+                                self.close(&mut stack);
+                            }
+                            stack.last_mut().unwrap().children.push(node);
+                        }
+                        ";" | "." | "!" | "?" if !token.is_word() => {
+                            while stack.len() > 1 {
+                                self.close(&mut stack);
+                            }
+                            stack[0].children.push(node);
+                            if units[u + 1..].iter().any(|n| !is_punct_unit(self.sent, n)) {
+                                self.close_all(&mut stack, &mut done);
+                            }
+                        }
+                        _ => stack.last_mut().unwrap().children.push(node),
+                    }
+                }
+            }
+        }
+        self.close_all(&mut stack, &mut done);
+        done
+    }
+
+    /// A coordinator joins clauses when a subject and finite verb follow
+    fn starts_clause(&self, rest: &[Unit]) -> bool {
+        match rest {
+            [Unit::Chunk(subject), Unit::Chunk(verb), ..] => subject.kind == ChunkKind::Np && verb.finite,
+            _ => false,
+        }
+    }
+
+    fn is_subject_pronoun(&self, node: &Node) -> bool {
+        match node {
+            Node::Chunk(c) => c.kind == ChunkKind::Np && c.end - c.start == 1 && is_subject_pronoun(&self.sent[c.start - self.base]),
+            _ => false,
+        }
+    }
+
+    fn close(&self, stack: &mut Vec<OpenClause>) {
+        let clause = stack.pop().unwrap();
+        if let Some(node) = self.clause_node(clause) {
+            stack.last_mut().unwrap().children.push(Node::Clause(node));
+        }
+    }
+
+    /// Close every clause; keep the main clause if it has content and start a fresh one.
+    fn close_all(&self, stack: &mut Vec<OpenClause>, done: &mut Vec<ClauseNode>) {
+        while stack.len() > 1 {
+            self.close(stack);
+        }
+        let main = std::mem::replace(&mut stack[0], OpenClause::new(ClauseKind::Main, None));
+        done.extend(self.clause_node(main));
+    }
+
+    fn clause_node(&self, clause: OpenClause) -> Option<ClauseNode> {
+        let start = node_start(clause.children.first()?);
+        let end = node_end(clause.children.last()?);
+        let span = self.byte_span(start - self.base..end - self.base);
+        Some(ClauseNode {
+            kind: clause.kind,
+            opener: clause.opener,
+            start,
+            end,
+            text: self.text[span.clone()].to_string(),
+            offsets: (self.to_offsets)(span),
+            children: clause.children,
+        })
+    }
+
+    fn chunk_node(&self, chunk: &Chunk) -> ChunkNode {
+        let span = self.byte_span(chunk.span.clone());
+        ChunkNode {
+            kind: chunk.kind,
+            start: self.base + chunk.span.start,
+            end: self.base + chunk.span.end,
+            head: self.base + chunk.head,
+            text: self.text[span.clone()].to_string(),
+            offsets: (self.to_offsets)(span),
+            children: chunk.object.iter().map(|o| self.chunk_node(o)).collect(),
+        }
+    }
+
+    /// Byte range of sentence tokens `tokens`
+    fn byte_span(&self, tokens: Range<usize>) -> Range<usize> {
+        self.sent[tokens.start].span.start..self.sent[tokens.end - 1].span.end
+    }
+}
+
+fn node_start(node: &Node) -> usize {
+    match node {
+        Node::Clause(c) => c.start,
+        Node::Chunk(c) => c.start,
+        Node::Token(t) => t.index,
+    }
+}
+
+fn node_end(node: &Node) -> usize {
+    match node {
+        Node::Clause(c) => c.end,
+        Node::Chunk(c) => c.end,
+        Node::Token(t) => t.index + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bracketed outline: clauses as `kind[...]`, chunks as `Np(text)`, tokens as their text.
+    fn outline(text: &str) -> String {
+        let tree = parse_text(text, TaggerMode::default());
+        let clauses: Vec<String> = tree.clauses.iter().map(|c| clause_outline(&tree, c)).collect();
+        clauses.join(" || ")
+    }
+
+    /// `outline` of one sentence given as `word/TAG` pairs, so the parse is
+    /// checked apart from what the tagger makes of the words.
+    fn outline_tagged(tagged: &str) -> String {
+        let pairs: Vec<(&str, &str)> = tagged.split_whitespace().map(|t| t.rsplit_once('/').unwrap()).collect();
+        let text = pairs.iter().map(|&(word, _)| word).collect::<Vec<_>>().join(" ");
+        let mut sent = tag_sentence(&tokenize_sentence(&text), TaggerMode::default());
+        assert_eq!(sent.len(), pairs.len());
+        for (token, &(_, tag)) in sent.iter_mut().zip(&pairs) {
+            token.tag = tag.to_string();
+        }
+
+        let utf16 = Utf16Index::new(&text);
+        let to_offsets = |range: Range<usize>| utf16.offsets(range);
+        let tree = ParseTree {
+            clauses: parse_sentence(&sent, &text, &to_offsets),
+            tokens: sent.iter().map(|t| ParsedToken {
+                text: text[t.span.clone()].to_string(),
+                tag: t.tag.clone(),
+                offsets: to_offsets(t.span.clone()),
+            }).collect(),
+        };
+        let clauses: Vec<String> = tree.clauses.iter().map(|c| clause_outline(&tree, c)).collect();
+        clauses.join(" || ")
+    }
+
+    fn clause_outline(tree: &ParseTree, clause: &ClauseNode) -> String {
+        let children: Vec<String> = clause.children.iter().map(|n| node_outline(tree, n)).collect();
+        format!("{}[{}]", clause.kind.name(), children.join(" "))
+    }
+
+    fn node_outline(tree: &ParseTree, node: &Node) -> String {
+        match node {
+            Node::Clause(c) => clause_outline(tree, c),
+            Node::Chunk(c) => format!("{:?}({})", c.kind, c.text),
+            Node::Token(t) => tree.tokens[t.index].text.clone(),
+        }
+    }
+
+    #[test]
+    fn relative_adverbial_and_complement_clauses() {
+        assert_eq!(outline("The man who lives next door is a doctor."), "main[Np(The man) relative[who Vp(lives) Np(next door)] Vp(is) Np(a doctor) .]");
+        assert_eq!(outline("She said that if it rained, they would stay."),
+            "main[Np(She) Vp(said) complement[that adverbial[if Np(it) Vp(rained)] , Np(they) Vp(would stay)] .]");
+        assert_eq!(outline("I wonder where he lives."), "main[Np(I) Vp(wonder) complement[where Np(he) Vp(lives)] .]");
+        assert_eq!(outline("When did you arrive?"), "main[When Vp(did you arrive) ?]");
+    }
+
+    #[test]
+    fn clauses_without_an_opener() {
+        assert_eq!(outline("The book I read was long."), "main[Np(The book) relative[Np(I) Vp(read)] Vp(was) Adjp(long) .]");
+        assert_eq!(outline("The man the police arrested was released."),
+            "main[Np(The man) relative[Np(the police) Vp(arrested)] Vp(was released) .]");
+        assert_eq!(outline("I think she is tired."), "main[Np(I) Vp(think) complement[Np(she) Vp(is) Adjp(tired)] .]");
+        assert_eq!(outline("Although she was tired it worked."),
+            "main[adverbial[Although Np(she) Vp(was) Adjp(tired)] Np(it) Vp(worked) .]");
+        assert_eq!(outline("I gave the boy the book."), "main[Np(I) Vp(gave) Np(the boy) Np(the book) .]");
+        assert_eq!(outline("The man standing there is my father."), "main[Np(The man) relative[Vp(standing) Advp(there)] Vp(is) Np(my father) .]");
+        assert_eq!(outline("How she did it is a mystery."), "main[complement[How Np(she) Vp(did) Np(it)] Vp(is) Np(a mystery) .]");
+    }
+
+    #[test]
+    fn result_and_nominal_clauses() {
+        assert_eq!(outline_tagged("It/PRP was/VBD so/RB cold/JJ that/IN we/PRP stayed/VBD inside/RB ./."),
+            "main[Np(It) Vp(was) Adjp(so cold) adverbial[that Np(we) Vp(stayed) Advp(inside)] .]");
+        assert_eq!(outline("What she said was true."), "main[complement[What Np(she) Vp(said)] Vp(was) Adjp(true) .]");
+        assert_eq!(outline("What did she say?"), "main[What Vp(did she say) ?]");
+        assert_eq!(outline("Where he lives is a secret."), "main[complement[Where Np(he) Vp(lives)] Vp(is) Np(a secret) .]");
+        assert_eq!(outline("Whether he comes doesn't matter."), "main[complement[Whether Np(he) Vp(comes)] Vp(doesn't matter) .]");
+        assert_eq!(outline("When he arrives, tell me."), "main[adverbial[When Np(he) Vp(arrives)] , Vp(tell) Np(me) .]");
+    }
+
+    #[test]
+    fn particles_questions_and_coordination() {
+        assert_eq!(outline("She gave up smoking."), "main[Np(She) Vp(gave up) Vp(smoking) .]");
+        assert_eq!(outline("Do you know the way?"), "main[Vp(Do you know) Np(the way) ?]");
+        assert_eq!(outline("He ran and she walked."), "main[Np(He) Vp(ran)] || main[and Np(she) Vp(walked) .]");
+    }
+
+    #[test]
+    fn the_tree_serializes_with_node_types_and_offsets() {
+        let tree = parse_text("I know that she is right.", TaggerMode::default());
+        let json = serde_json::to_value(&tree).unwrap();
+        let complement = &json["clauses"][0]["children"][2];
+        assert_eq!(complement["type"], "clause");
+        assert_eq!(complement["kind"], "complement");
+        assert_eq!(complement["text"], "that she is right");
+        assert_eq!(complement["children"][0]["type"], "token");
+        assert_eq!(complement["children"][1]["kind"], "NP");
+        assert_eq!(json["tokens"][2]["offsets"]["start"], 7);
+    }
+}
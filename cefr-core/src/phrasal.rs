@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+use phf::phf_map;
+use crate::dictionary::{CEFRLevel, DICT};
+use crate::pos::TaggedToken;
+
+/// Most tokens allowed between a verb and its particle ("figure it all out").
+const MAX_GAP: usize = 3;
+
+/// Adverbial particles that combine with verbs.
+const PARTICLES: &[&str] = &[
+    "up", "down", "out", "off", "on", "in", "over", "away", "back", "around", "round",
+    "about", "through", "along", "aside", "apart", "together", "forward", "across", "by",
+];
+
+/// Common phrasal verbs and their English Vocabulary Profile level.
+/// Keyed "verb particle" with the verb in its base form.
+static PHRASAL_VERBS: phf::Map<&'static str, CEFRLevel> = phf_map! {
+    "get up" => CEFRLevel::A1, "wake up" => CEFRLevel::A1, "sit down" => CEFRLevel::A1,
+    "stand up" => CEFRLevel::A1, "come in" => CEFRLevel::A1, "come back" => CEFRLevel::A1,
+    "go out" => CEFRLevel::A1, "go back" => CEFRLevel::A1, "put on" => CEFRLevel::A2,
+    "take off" => CEFRLevel::A2, "turn on" => CEFRLevel::A2, "turn off" => CEFRLevel::A2,
+    "switch on" => CEFRLevel::A2, "switch off" => CEFRLevel::A2, "pick up" => CEFRLevel::A2,
+    "find out" => CEFRLevel::A2, "give up" => CEFRLevel::A2, "give back" => CEFRLevel::A2,
+    "throw away" => CEFRLevel::A2, "write down" => CEFRLevel::A2, "fill in" => CEFRLevel::A2,
+    "try on" => CEFRLevel::A2, "look up" => CEFRLevel::A2, "grow up" => CEFRLevel::A2,
+    "get on" => CEFRLevel::A2, "get off" => CEFRLevel::A2, "go on" => CEFRLevel::A2,
+    "lie down" => CEFRLevel::A2, "clean up" => CEFRLevel::A2, "turn down" => CEFRLevel::B1,
+    "turn up" => CEFRLevel::B1, "put away" => CEFRLevel::B1, "put down" => CEFRLevel::B1,
+    "put out" => CEFRLevel::B1, "put up" => CEFRLevel::B1, "take back" => CEFRLevel::B1,
+    "take out" => CEFRLevel::B1, "take up" => CEFRLevel::B1, "work out" => CEFRLevel::B1,
+    "figure out" => CEFRLevel::B1, "fill out" => CEFRLevel::B1, "set up" => CEFRLevel::B1,
+    "point out" => CEFRLevel::B1, "make up" => CEFRLevel::B1, "hand in" => CEFRLevel::B1,
+    "give away" => CEFRLevel::B1, "give out" => CEFRLevel::B1, "bring back" => CEFRLevel::B1,
+    "check out" => CEFRLevel::B1, "cut down" => CEFRLevel::B1, "cut off" => CEFRLevel::B1,
+    "drop off" => CEFRLevel::B1, "look over" => CEFRLevel::B1, "pay back" => CEFRLevel::B1,
+    "call back" => CEFRLevel::B1, "leave out" => CEFRLevel::B1, "try out" => CEFRLevel::B1,
+    "carry on" => CEFRLevel::B1, "hang up" => CEFRLevel::B1, "set off" => CEFRLevel::B1,
+    "carry out" => CEFRLevel::B2, "bring up" => CEFRLevel::B2, "call off" => CEFRLevel::B2,
+    "put off" => CEFRLevel::B2, "let down" => CEFRLevel::B2, "sort out" => CEFRLevel::B2,
+    "turn over" => CEFRLevel::B2, "hand over" => CEFRLevel::B2, "rule out" => CEFRLevel::B2,
+    "think over" => CEFRLevel::B2, "talk over" => CEFRLevel::B2, "back up" => CEFRLevel::B2,
+    "break down" => CEFRLevel::B2, "bring about" => CEFRLevel::B2, "bring out" => CEFRLevel::B2,
+    "take on" => CEFRLevel::B2, "take over" => CEFRLevel::B2, "take in" => CEFRLevel::B2,
+    "pull down" => CEFRLevel::B2, "knock down" => CEFRLevel::B2, "wipe out" => CEFRLevel::B2,
+    "give off" => CEFRLevel::C1, "hold back" => CEFRLevel::C1, "play down" => CEFRLevel::C1,
+    "shrug off" => CEFRLevel::C1, "write off" => CEFRLevel::C1, "single out" => CEFRLevel::C1,
+    "talk round" => CEFRLevel::C2, "gloss over" => CEFRLevel::C2, "fob off" => CEFRLevel::C2,
+};
+
+lazy_static! {
+    /// Verb + particle entries of the vocabulary lists ("dole out", "mull over")
+    /// so they are also found when split by an object.
+    static ref DICTIONARY_PHRASAL_VERBS: HashMap<String, CEFRLevel> = DICT.phrases.iter()
+        .filter_map(|(phrase, level)| {
+            let lower = phrase.to_ascii_lowercase();
+            let (verb, particle) = lower.split_once(' ')?;
+            let is_verb = DICT.lookup_all(verb).is_some_and(|es| es.iter().any(|e| e.pos == "verb"));
+            (is_verb && PARTICLES.contains(&particle)).then(|| (lower.clone(), level.clone()))
+        })
+        .collect();
+}
+
+/// A verb + particle combination, possibly split by its object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhrasalVerbMatch {
+    /// "verb particle", verb in its base form
+    pub text: String,
+    pub level: CEFRLevel,
+    pub verb: usize,
+    pub particle: usize,
+}
+
+fn is_particle(word: &str) -> bool {
+    PARTICLES.contains(&word.to_ascii_lowercase().as_str())
+}
+
+fn phrasal_level(key: &str) -> Option<CEFRLevel> {
+    PHRASAL_VERBS.get(key).cloned().or_else(|| DICTIONARY_PHRASAL_VERBS.get(key).cloned())
+}
+
+/// Tokens that may sit between verb and particle: a pronoun or a short noun
+/// phrase ("her", "the lights", "it all").
+fn is_gap_token(token: &TaggedToken) -> bool {
+    let lower = token.word.to_ascii_lowercase();
+    matches!(token.tag.as_str(), "PRP" | "PRP$" | "DT" | "PDT" | "CD" | "POS")
+        || token.tag.starts_with("NN")
+        || token.tag.starts_with("JJ")
+        || matches!(lower.as_str(), "all" | "both" | "each" | "everything" | "something" | "anything" | "nothing")
+}
+
+/// A particle directly followed by a noun phrase is a preposition heading it
+/// ("put the book on the table"), not the second half of a split phrasal verb.
+/// Time words tagged as nouns ("pick her up tomorrow") don't count.
+fn starts_noun_phrase(token: Option<&TaggedToken>) -> bool {
+    token.is_some_and(|t| {
+        let lower = t.word.to_ascii_lowercase();
+        if matches!(lower.as_str(), "today" | "tonight" | "tomorrow" | "yesterday" | "now" | "again") {
+            return false;
+        }
+        matches!(t.tag.as_str(), "DT" | "PRP$" | "CD") || t.tag.starts_with("NN") || t.tag.starts_with("JJ")
+    })
+}
+
+/// Find phrasal verbs in one tagged sentence. `forms[i]` are token `i`'s
+/// spellings from `phrases::token_forms` (surface form and lemma).
+pub fn detect_phrasal_verbs(sent: &[TaggedToken], forms: &[Vec<String>]) -> Vec<PhrasalVerbMatch> {
+    let mut matches = Vec::new();
+    let mut used = vec![false; sent.len()];
+
+    for (i, token) in sent.iter().enumerate() {
+        if !token.tag.starts_with('V') || used[i] {
+            continue;
+        }
+
+        for gap in 0..=MAX_GAP {
+            let j = i + 1 + gap;
+            let Some(candidate) = sent.get(j) else { break };
+
+            if is_particle(&candidate.word) && !used[j] {
+                let particle = candidate.word.to_ascii_lowercase();
+                let hit = forms[i].iter().find_map(|verb| {
+                    let key = format!("{} {}", verb, particle);
+                    phrasal_level(&key).map(|level| (key, level))
+                });
+
+                if let Some((text, level)) = hit {
+                    if gap == 0 || !starts_noun_phrase(sent.get(j + 1)) {
+                        used[i] = true;
+                        used[j] = true;
+                        matches.push(PhrasalVerbMatch { text, level, verb: i, particle: j });
+                        break;
+                    }
+                }
+            }
+
+            // Only a pronoun or short noun phrase may separate verb and particle
+            if !is_gap_token(candidate) {
+                break;
+            }
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phrases::token_forms;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    fn detect(text: &str) -> Vec<(String, usize, usize)> {
+        let sent = tag_sentence(&tokenize_sentence(text), TaggerMode::default());
+        let forms: Vec<Vec<String>> = sent.iter().map(token_forms).collect();
+        detect_phrasal_verbs(&sent, &forms).into_iter().map(|m| (m.text, m.verb, m.particle)).collect()
+    }
+
+    #[test]
+    fn finds_contiguous_phrasal_verbs_through_the_verb_lemma() {
+        assert_eq!(detect("She gave up smoking."), vec![("give up".to_string(), 1, 2)]);
+    }
+
+    #[test]
+    fn allows_a_pronoun_or_short_noun_phrase_before_the_particle() {
+        assert_eq!(detect("Please turn the lights off."), vec![("turn off".to_string(), 1, 4)]);
+        assert_eq!(detect("I will pick her up tomorrow."), vec![("pick up".to_string(), 2, 4)]);
+        assert_eq!(detect("We figured it all out."), vec![("figure out".to_string(), 1, 4)]);
+    }
+
+    #[test]
+    fn a_particle_heading_a_noun_phrase_is_a_preposition() {
+        assert!(detect("Put the book on the table.").is_empty());
+    }
+
+    #[test]
+    fn the_gap_is_bounded() {
+        assert!(detect("Turn the big old red lights off.").is_empty());
+    }
+
+    #[test]
+    fn reports_the_level() {
+        let sent = tag_sentence(&tokenize_sentence("They called the meeting off."), TaggerMode::default());
+        let forms: Vec<Vec<String>> = sent.iter().map(token_forms).collect();
+        let found = detect_phrasal_verbs(&sent, &forms);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].level, CEFRLevel::B2);
+    }
+}
@@ -0,0 +1,166 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use fst::raw::{Fst, Node, Output};
+use crate::dictionary::DICT;
+use crate::lemmatizer::lemmatize;
+use crate::pos::TaggedToken;
+
+/// A multi-word expression found in a sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhraseMatch {
+    /// Index into `Dictionary::phrases`
+    pub phrase_id: usize,
+    /// Covered tokens: `start..end` within the sentence
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Matches dictionary phrases against a tagged token stream rather than raw
+/// text, so hits always start and end on word boundaries ("a lot" never fires
+/// inside "a lottery") and inflected forms match through their lemma
+/// ("took part in" -> "take part in").
+pub struct PhraseMatcher {
+    /// Phrase words, ASCII-lowercased and joined by single spaces -> index
+    /// into `Dictionary::phrases`; see `dict_source::phrase_automaton`
+    automaton: Fst<Cow<'static, [u8]>>,
+}
+
+impl PhraseMatcher {
+    /// A matcher over ad-hoc `phrases`, compiled on the spot; the
+    /// dictionary's own is precompiled by build.rs (`from_static`).
+    #[cfg(test)]
+    pub fn new<'a>(phrases: impl IntoIterator<Item = &'a str>) -> Self {
+        let automaton = Fst::new(Cow::Owned(crate::dict_source::phrase_automaton(phrases))).expect("phrase automaton is well formed");
+        PhraseMatcher { automaton }
+    }
+
+    pub fn from_static(bytes: &'static [u8]) -> Self {
+        let automaton = Fst::new(Cow::Borrowed(bytes)).expect("precompiled phrase index is corrupt");
+        PhraseMatcher { automaton }
+    }
+
+    /// Leftmost-longest, non-overlapping matches over one sentence.
+    /// `forms[i]` lists the spellings token `i` may match as (see `token_forms`).
+    pub fn find_iter(&self, forms: &[Vec<String>]) -> Vec<PhraseMatch> {
+        let mut matches = Vec::new();
+        let mut i = 0;
+
+        while i < forms.len() {
+            let mut best = None;
+            self.extend(self.automaton.root(), Output::zero(), forms, i, &mut best);
+
+            match best {
+                Some((end, id)) => {
+                    matches.push(PhraseMatch { phrase_id: id, start: i, end });
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+
+        matches
+    }
+
+    /// Walk the automaton through each form of token `at`, recording in
+    /// `best` the `(end, phrase id)` of the longest phrase completed so far
+    /// (lowest id on ties), then carry on into the next token.
+    fn extend(&self, node: Node<'_>, out: Output, forms: &[Vec<String>], at: usize, best: &mut Option<(usize, usize)>) {
+        let Some(token_forms) = forms.get(at) else { return };
+        for form in token_forms {
+            let Some((node, out)) = self.step(node, out, form.as_bytes()) else { continue };
+            if node.is_final() {
+                let found = (at + 1, out.cat(node.final_output()).value() as usize);
+                let longer = match *best {
+                    Some((end, id)) => (found.0, Reverse(found.1)) > (end, Reverse(id)),
+                    None => true,
+                };
+                if longer {
+                    *best = Some(found);
+                }
+            }
+            if let Some((node, out)) = self.step(node, out, b" ") {
+                self.extend(node, out, forms, at + 1, best);
+            }
+        }
+    }
+
+    fn step<'f>(&'f self, mut node: Node<'f>, mut out: Output, bytes: &[u8]) -> Option<(Node<'f>, Output)> {
+        for &byte in bytes {
+            let transition = node.transition(node.find_input(byte)?);
+            out = out.cat(transition.out);
+            node = self.automaton.node(transition.addr);
+        }
+        Some((node, out))
+    }
+}
+
+/// Spellings a token can match a phrase word as: its ASCII-lowercased surface
+/// form plus its lemma (dictionary lemma when known, lemmatizer guess otherwise).
+/// Punctuation only matches itself, so phrases never run across a comma.
+pub fn token_forms(token: &TaggedToken) -> Vec<String> {
+    let surface = token.word.to_ascii_lowercase();
+    if !token.is_word() {
+        return vec![surface];
+    }
+    let lemma = match DICT.lookup(&token.word, Some(&token.tag)) {
+        Some(entry) => entry.lemma.to_ascii_lowercase(),
+        None => lemmatize(&token.word, Some(&token.tag)),
+    };
+
+    if lemma == surface {
+        vec![surface]
+    } else {
+        vec![surface, lemma]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    fn forms(text: &str) -> Vec<Vec<String>> {
+        tag_sentence(&tokenize_sentence(text), TaggerMode::default()).iter().map(token_forms).collect()
+    }
+
+    fn spans(matcher: &PhraseMatcher, text: &str) -> Vec<(usize, usize, usize)> {
+        matcher.find_iter(&forms(text)).into_iter().map(|m| (m.phrase_id, m.start, m.end)).collect()
+    }
+
+    #[test]
+    fn matches_whole_words_only() {
+        let matcher = PhraseMatcher::new(["a lot"]);
+        assert_eq!(spans(&matcher, "I read a lot."), vec![(0, 2, 4)]);
+        assert!(spans(&matcher, "I won a lottery.").is_empty());
+    }
+
+    #[test]
+    fn folds_case_at_sentence_start() {
+        let matcher = PhraseMatcher::new(["in spite of"]);
+        assert_eq!(spans(&matcher, "In spite of the rain we went out."), vec![(0, 0, 3)]);
+    }
+
+    #[test]
+    fn inflected_forms_match_through_their_lemma() {
+        let matcher = PhraseMatcher::new(["take part in"]);
+        assert_eq!(spans(&matcher, "She took part in the race."), vec![(0, 1, 4)]);
+        assert_eq!(spans(&matcher, "They are taking part in it."), vec![(0, 2, 5)]);
+    }
+
+    #[test]
+    fn prefers_the_longest_phrase_and_never_crosses_punctuation() {
+        let matcher = PhraseMatcher::new(["a lot", "a lot of"]);
+        assert_eq!(spans(&matcher, "A lot of people came."), vec![(1, 0, 3)]);
+        assert!(spans(&matcher, "Give me a, lot.").is_empty());
+    }
+
+    #[test]
+    fn dictionary_phrases_are_matched() {
+        let found: Vec<&str> = DICT.phrase_matcher.find_iter(&forms("Because of the rain we ate ice creams."))
+            .into_iter()
+            .map(|m| DICT.phrases[m.phrase_id].0)
+            .collect();
+        assert_eq!(found, vec!["because of", "ice cream"]);
+    }
+}
@@ -1,15 +1,328 @@
-use crate::dictionary::DICT;
+use std::ops::Range;
+use lazy_static::lazy_static;
+use crate::brill::{apply_rules, parse_rules, Rule};
+use crate::clitics::{resolve_clitics, Clitic};
+use crate::dictionary::{pos_for_tag, tag_fits_pos, DICT};
+use crate::discourse::is_common_name;
+use crate::hmm::HmmModel;
+use crate::lemmatizer::{is_past_form, lemmatize};
+use crate::corpus::sentence_text;
+use crate::tokenizer::{tokenize_sentence, Token, TokenKind};
 
 pub struct TaggedToken {
     pub word: String,
     pub tag: String,
+    pub kind: TokenKind,
+    /// Reading of an `'s` / `'d` clitic; `word` holds the expansion for verb readings
+    pub clitic: Option<Clitic>,
+    /// Byte range in the tokenized text
+    pub span: Range<usize>,
 }
 
-pub fn tag_sentence(tokens: &[String]) -> Vec<TaggedToken> {
+impl TaggedToken {
+    pub fn is_word(&self) -> bool {
+        self.kind == TokenKind::Word
+    }
+
+    /// Counted in `word_count` and reported in `details`: words plus
+    /// non-lexical tokens such as numbers and URLs.
+    pub fn is_countable(&self) -> bool {
+        self.is_word() || self.kind.is_non_lexical()
+    }
+}
+
+/// Which tagger `tag_sentence` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaggerMode {
+    /// Second-order HMM trained from assets/pos_corpus.txt. The default;
+    /// falls back to `Rules` if the embedded model can't be loaded
+    #[default]
+    Hmm,
+    /// Dictionary candidates with hand-written disambiguation, then the
+    /// transformation rules learned into assets/pos_rules.txt
+    Rules,
+    /// `Rules` without the learned transformations: the starting point
+    /// src/bin/train_rules.rs learns from
+    RulesBase,
+}
+
+impl TaggerMode {
+    /// "hmm", "rules" or "rules-base".
+    pub fn from_name(name: &str) -> Option<TaggerMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "hmm" => Some(TaggerMode::Hmm),
+            "rules" => Some(TaggerMode::Rules),
+            "rules-base" => Some(TaggerMode::RulesBase),
+            _ => None,
+        }
+    }
+}
+
+lazy_static! {
+    // Built by `cargo run --bin train_tagger`
+    static ref MODEL: Option<HmmModel> = serde_json::from_str(include_str!("../assets/pos_model.json")).ok();
+    // Built by `cargo run --bin train_rules`
+    static ref RULES: Vec<Rule> = parse_rules(include_str!("../assets/pos_rules.txt")).unwrap_or_default();
+}
+
+/// Weight of a dictionary reading the corpus never showed for a known word
+/// (ln 0.05): "book" was only seen as a noun but can still be tagged VB.
+const UNSEEN_READING_LOG_WEIGHT: f32 = -3.0;
+
+pub fn tag_sentence(tokens: &[Token], mode: TaggerMode) -> Vec<TaggedToken> {
+    let model = MODEL.as_ref().filter(|_| mode == TaggerMode::Hmm);
+    let mut results = match model {
+        Some(model) => tag_with_model(tokens, model),
+        None => tag_with_rules(tokens),
+    };
+
+    // The rule tagger's coarse tags get their full Penn Treebank form from
+    // morphology and context
+    if model.is_none() {
+        refine_tags(&mut results);
+    }
+
+    resolve_clitics(&mut results);
+
+    // Learned Brill transformation rules (Second Pass)
+    if model.is_none() && mode != TaggerMode::RulesBase {
+        apply_learned_rules(&mut results);
+    }
+
+    results
+}
+
+/// The tagger's reading of one pre-tokenized gold word.
+pub struct GoldPrediction {
+    pub tag: String,
+    /// See `is_rule_target`
+    pub rule_target: bool,
+}
+
+/// Tag the words of a gold-tagged sentence by rebuilding its text and
+/// tokenizing it as usual. `None` where no token starts at a word (the
+/// tokenizer split the text differently).
+pub fn tag_gold_words(words: &[String], mode: TaggerMode) -> Vec<Option<GoldPrediction>> {
+    let (text, starts) = sentence_text(words);
+    let tokens = tokenize_sentence(&text);
+    let tagged = tag_sentence(&tokens, mode);
+    starts
+        .iter()
+        .map(|&start| {
+            tagged.iter().find(|t| t.span.start == start).map(|t| GoldPrediction {
+                tag: t.tag.clone(),
+                rule_target: is_rule_target(t),
+            })
+        })
+        .collect()
+}
+
+/// Whether transformation rules may retag a token: words, but not
+/// punctuation, numbers or resolved clitics.
+pub fn is_rule_target(token: &TaggedToken) -> bool {
+    token.is_word() && token.clitic.is_none()
+}
+
+fn apply_learned_rules(tokens: &mut [TaggedToken]) {
+    let words: Vec<String> = tokens.iter().map(|t| t.word.to_lowercase()).collect();
+    let fixed: Vec<bool> = tokens.iter().map(|t| !is_rule_target(t)).collect();
+    let mut tags: Vec<String> = tokens.iter().map(|t| t.tag.clone()).collect();
+    apply_rules(&RULES, &words, &mut tags, &fixed);
+    for (token, tag) in tokens.iter_mut().zip(tags) {
+        token.tag = tag;
+    }
+}
+
+/// Tags that don't depend on context: punctuation and non-lexical tokens.
+fn fixed_tag(token: &Token, in_quote: &mut bool) -> Option<&'static str> {
+    match token.kind {
+        // Straight quotes open and close alternately
+        TokenKind::Punct if token.text == "\"" => {
+            *in_quote = !*in_quote;
+            Some(if *in_quote { "``" } else { "''" })
+        }
+        TokenKind::Punct => Some(punct_tag(&token.text)),
+        TokenKind::Ordinal => Some("JJ"),
+        TokenKind::Url | TokenKind::Email => Some("NN"),
+        kind if kind.is_non_lexical() => Some("CD"),
+        _ => None,
+    }
+}
+
+fn tagged(token: &Token, tag: &str) -> TaggedToken {
+    TaggedToken {
+        word: token.text.clone(),
+        tag: tag.to_string(),
+        kind: token.kind,
+        clitic: None,
+        span: token.span.clone(),
+    }
+}
+
+fn tag_with_model(tokens: &[Token], model: &HmmModel) -> Vec<TaggedToken> {
+    let mut in_quote = false;
+    let fixed: Vec<Option<&str>> = tokens.iter().map(|t| fixed_tag(t, &mut in_quote)).collect();
+    let first_word = tokens.iter().position(|t| t.kind == TokenKind::Word);
+
+    // Fixed tags outside the model's tag set simply drop out of the chain
+    let candidates: Vec<Vec<(usize, f32)>> = tokens
+        .iter()
+        .zip(&fixed)
+        .enumerate()
+        .map(|(i, (token, fixed))| match fixed {
+            Some(tag) => model.tag_index(tag).map(|t| vec![(t, 0.0)]).unwrap_or_default(),
+            None if token.kind == TokenKind::Clitic => clitic_emissions(model, &token.text),
+            None => word_emissions(model, &token.text, first_word == Some(i)),
+        })
+        .collect();
+
+    let path = model.viterbi(&candidates);
+    tokens
+        .iter()
+        .zip(fixed)
+        .zip(path)
+        .map(|((token, fixed), t)| {
+            let tag = fixed.or_else(|| model.tags.get(t).map(String::as_str)).unwrap_or("NN");
+            tagged(token, tag)
+        })
+        .collect()
+}
+
+/// 's is VBZ or POS and 'd is MD or VBD; resolve_clitics picks the exact reading.
+fn clitic_emissions(model: &HmmModel, clitic: &str) -> Vec<(usize, f32)> {
+    let readings: &[&str] = if clitic.eq_ignore_ascii_case("'d") { &["MD", "VBD"] } else { &["VBZ", "POS"] };
+    let known = model.known_emissions(&clitic.to_lowercase()).unwrap_or_default();
+    readings
+        .iter()
+        .filter_map(|tag| model.tag_index(tag))
+        .map(|t| known.iter().find(|&&(k, _)| k == t).copied().unwrap_or((t, UNSEEN_READING_LOG_WEIGHT)))
+        .collect()
+}
+
+fn is_open_class(tag: &str) -> bool {
+    matches!(pos_for_tag(tag), "noun" | "verb" | "adj" | "adv")
+}
+
+/// Emission scores for a word: the corpus readings of a known word plus any
+/// open-class reading the dictionary allows, or suffix-model scores for an
+/// unknown word with readings outside the dictionary's parts of speech
+/// weighted down.
+fn word_emissions(model: &HmmModel, word: &str, sentence_initial: bool) -> Vec<(usize, f32)> {
+    let allowed = get_candidates(word);
+    let lower = word.to_lowercase();
+    // Capitalization only says "proper noun" when it isn't just sentence case
+    let capitalized = word.chars().next().is_some_and(char::is_uppercase)
+        && !(sentence_initial && (model.known_emissions(&lower).is_some() || DICT.lookup_all(&lower).is_some()));
+    let pos_of = |t: usize| pos_for_tag(&model.tags[t]);
+    // Past forms ("wrote", "glimmered") are VBD/VBN when they are verbs at all
+    let past = is_past_form(word);
+    let fits = |t: usize| match model.tags[t].as_str() {
+        "VBD" | "VBN" if past => true,
+        tag if past && tag.starts_with("VB") => false,
+        // Comparative and superlative tags need an -er/-est inflection ("fair" is no JJR)
+        "JJR" | "JJS" | "RBR" | "RBS" => is_graded_form(&lower),
+        _ => allowed.contains(&pos_of(t)) || allowed.is_empty() && !past,
+    };
+
+    let known = model.known_emissions(word);
+    let unknown = known.is_none();
+    let mut scores = match known {
+        Some(mut known) => {
+            // Open-class readings the corpus never showed ("a can of beans",
+            // "the rain"): the likeliest tag of each stays within
+            // UNSEEN_READING_LOG_WEIGHT of the word's best seen one
+            let seen: Vec<&str> = known.iter().map(|&(t, _)| pos_of(t)).collect();
+            let top = known.iter().map(|&(_, s)| s).max_by(f32::total_cmp).unwrap_or(0.0);
+            let suffix: Vec<(usize, f32)> = model
+                .suffix_emissions(word, capitalized)
+                .into_iter()
+                .filter(|&(t, _)| allowed.contains(&pos_of(t)) && !seen.contains(&pos_of(t)))
+                .collect();
+            let best_of = |pos: &str| suffix.iter().filter(|&&(t, _)| pos_of(t) == pos).map(|&(_, s)| s).max_by(f32::total_cmp);
+            for &(t, score) in &suffix {
+                let score = if best_of(pos_of(t)) == Some(score) { score.max(top) } else { score };
+                known.push((t, score + UNSEEN_READING_LOG_WEIGHT));
+            }
+            known
+        }
+        None => {
+            let suffix = model.suffix_emissions(word, capitalized);
+            // A dictionary word's readings are all plausible however rare its
+            // suffix is for them ("bank" ends like "thank"): the likeliest tag
+            // of each reading comes close to the best one, and context decides
+            let best = suffix.iter().filter(|&&(t, _)| fits(t)).map(|&(_, s)| s).max_by(f32::total_cmp);
+            let best_of = |pos: &str| suffix.iter().filter(|&&(t, _)| fits(t) && pos_of(t) == pos).map(|&(_, s)| s).max_by(f32::total_cmp);
+            let floor = best.filter(|_| !allowed.is_empty()).map_or(f32::NEG_INFINITY, |b| b + UNSEEN_READING_LOG_WEIGHT / 3.0);
+            let mut scores: Vec<(usize, f32)> = suffix
+                .iter()
+                .map(|&(t, score)| match fits(t) {
+                    true if best_of(pos_of(t)) == Some(score) => (t, score.max(floor)),
+                    true => (t, score),
+                    false => (t, score + UNSEEN_READING_LOG_WEIGHT),
+                })
+                .collect();
+            // The suffix model only knows open classes; closed-class readings
+            // the dictionary gives ("might" MD, "under" IN) score like its
+            // best open-class one, or neutrally without one
+            let closed = best.unwrap_or(0.0);
+            scores.extend(
+                (0..model.tags.len())
+                    .filter(|&t| !is_open_class(&model.tags[t]) && allowed.iter().any(|pos| tag_fits_pos(&model.tags[t], pos)))
+                    .map(|t| (t, closed)),
+            );
+            scores
+        }
+    };
+
+    // VBD vs VBN is up to the context ("was sold", "sold it"), not the word,
+    // unless the form is only a participle ("written"); auxiliaries keep theirs
+    if past && !matches!(lemmatize(&lower, Some("VB")).as_str(), "be" | "have" | "do") {
+        let forms: &[&str] = if is_participle_only(&lower) { &["VBN"] } else { &["VBD", "VBN"] };
+        let past_tags: Vec<usize> = forms.iter().filter_map(|tag| model.tag_index(tag)).collect();
+        let all_past: Vec<usize> = ["VBD", "VBN"].iter().filter_map(|tag| model.tag_index(tag)).collect();
+        let best = scores.iter().filter(|(t, _)| all_past.contains(t)).map(|&(_, s)| s).max_by(f32::total_cmp);
+        if let Some(best) = best {
+            scores.retain(|(t, _)| !all_past.contains(t));
+            scores.extend(past_tags.iter().map(|&t| (t, best)));
+        }
+    }
+
+    // Likewise VB vs VBP for the base form of a word the corpus never saw
+    // ("will believe", "we believe")
+    if unknown {
+        let base: Vec<usize> = ["VB", "VBP"].iter().filter_map(|tag| model.tag_index(tag)).collect();
+        let best = scores.iter().filter(|(t, _)| base.contains(t)).map(|&(_, s)| s).max_by(f32::total_cmp);
+        if let Some(best) = best {
+            scores.retain(|(t, _)| !base.contains(t));
+            scores.extend(base.iter().map(|&t| (t, best)));
+        }
+    }
+    scores
+}
+
+fn tag_with_rules(tokens: &[Token]) -> Vec<TaggedToken> {
     let mut results = Vec::new();
     let mut last_tag = "START".to_string();
+    let mut in_quote = false;
+
+    for token in tokens {
+        if let Some(tag) = fixed_tag(token, &mut in_quote) {
+            results.push(tagged(token, tag));
+            if token.kind.is_non_lexical() {
+                last_tag = pos_for_tag(tag).to_string();
+            }
+            continue;
+        }
+
+        // 's / 'd: placeholder until resolve_clitics sees the following words
+        if token.kind == TokenKind::Clitic {
+            results.push(tagged(token, "POS"));
+            last_tag = if token.text.eq_ignore_ascii_case("'d") { "modal" } else { "verb" }.to_string();
+            continue;
+        }
 
-    for (_i, token) in tokens.iter().enumerate() {
+        let span = token.span.clone();
+        let token = &token.text;
         let candidates = get_candidates(token);
         
         // Heuristic fallback for unknown words
@@ -20,36 +333,84 @@ pub fn tag_sentence(tokens: &[String]) -> Vec<TaggedToken> {
         };
 
         // Disambiguation
-        let best_pos = disambiguate(token, &candidates, &last_tag);
+        let best_pos = disambiguate(&candidates, &last_tag);
         
         let final_tag = transform_tag(best_pos);
         results.push(TaggedToken {
             word: token.clone(),
             tag: final_tag.clone(),
+            kind: TokenKind::Word,
+            clitic: None,
+            span,
         });
         
-        // Pass the simplified POS (not PTB) to next step for easier rules
-        // Pass the simplified POS (not PTB) to next step for easier rules
-        last_tag = best_pos.to_string();
+        // Pass the simplified POS (not PTB) to next step for easier rules.
+        // "Please" still leaves an imperative at the start ("Please turn ..."),
+        // and a plain adverb keeps what came before ("we often visit")
+        let transparent = last_tag == "START" && token.eq_ignore_ascii_case("please")
+            || candidates == ["adv"];
+        if !transparent {
+            last_tag = best_pos.to_string();
+        }
+        // "do not go": negated do-support leaves a bare verb, as a modal does
+        let do_support = results.len() > 1 && matches!(results[results.len() - 2].word.to_lowercase().as_str(), "do" | "does" | "did");
+        if token.eq_ignore_ascii_case("not") && do_support {
+            last_tag = "modal".to_string();
+        }
     }
-    
-    // Apply Brill Tagger transformation rules (Second Pass)
-    apply_context_rules(&mut results);
-    
+
     results
 }
 
+/// Penn Treebank tag for a punctuation token.
+fn punct_tag(text: &str) -> &'static str {
+    match text.chars().next() {
+        Some(',') => ",",
+        Some('.') if text.len() > 1 => ":", // ellipsis
+        Some('.' | '!' | '?') => ".",
+        Some(':' | ';' | '-' | '\u{2013}' | '\u{2014}') => ":",
+        Some('(' | '[' | '{') => "-LRB-",
+        Some(')' | ']' | '}') => "-RRB-",
+        Some('"' | '\u{201C}' | '\u{2018}' | '`') => "``",
+        Some('\'' | '\u{201D}' | '\u{2019}') => "''",
+        Some('$' | '\u{00A3}' | '\u{20AC}') => "$",
+        Some('#') => "#",
+        _ => "SYM",
+    }
+}
+
 fn get_candidates(token: &str) -> Vec<&str> {
     let mut candidates = Vec::new();
     if let Some(entries) = DICT.lookup_all(token) {
         for e in entries {
-            candidates.push(e.pos.as_str());
+            // Core-list entries have no POS; only the vocabulary profiles
+            // constrain the tag, through the row's lemma ("bigger" -> big, adj)
+            // as far as the inflection allows ("sleeping" is no noun)
+            if !e.pos.is_empty() {
+                candidates.push(e.pos);
+            } else if e.lemma != token {
+                let lemma_entries = DICT.lookup_all(e.lemma).into_iter().flatten();
+                candidates.extend(lemma_entries.map(|l| l.pos).filter(|pos| !pos.is_empty() && inflection_allows(token, pos)));
+            }
         }
     }
     candidates.dedup(); // remove duplicates
     candidates
 }
 
+/// Whether an inflected form can have the POS of its lemma: -ing and past
+/// forms ("walked", "ran") are verbs, -er/-est forms adjectives or adverbs.
+fn inflection_allows(token: &str, pos: &str) -> bool {
+    let lower = token.to_lowercase();
+    if lower.ends_with("ing") || lower.ends_with("ed") || is_past_form(&lower) {
+        pos == "verb"
+    } else if lower.ends_with("er") || lower.ends_with("est") {
+        matches!(pos, "adj" | "adv")
+    } else {
+        true
+    }
+}
+
 fn guess_candidates(token: &str) -> Vec<&str> {
     // Basic morphology guessing
     if token.ends_with("ly") { vec!["adv"] }
@@ -60,14 +421,14 @@ fn guess_candidates(token: &str) -> Vec<&str> {
     else { vec!["noun"] } // Default to noun
 }
 
-fn disambiguate<'a>(_token: &str, candidates: &'a [&str], last_tag: &str) -> &'a str {
+fn disambiguate<'a>(candidates: &'a [&str], last_tag: &str) -> &'a str {
     if candidates.len() == 1 {
         return candidates[0];
     }
 
     // Rule 1: Determiner (DT) -> Noun
-    // e.g. "a book" (book: noun/verb) -> noun
-    if last_tag == "determiner" || last_tag == "adj" {
+    // e.g. "a book", "3 books" (book: noun/verb) -> noun
+    if last_tag == "determiner" || last_tag == "adj" || last_tag == "num" {
         if candidates.contains(&"noun") { return "noun"; }
     }
 
@@ -83,11 +444,6 @@ fn disambiguate<'a>(_token: &str, candidates: &'a [&str], last_tag: &str) -> &'a
         if candidates.contains(&"verb") { return "verb"; }
     }
     
-    // Rule 4: Pronoun -> Verb (Subject-Verb)
-    if last_tag == "pronoun" || last_tag == "noun" {
-        if candidates.contains(&"verb") { return "verb"; }
-    }
-
     // Default: Prefer Noun if available, else first
     if candidates.contains(&"noun") { "noun" }
     else { candidates[0] }
@@ -105,79 +461,299 @@ fn transform_tag(t: &str) -> String {
         "pronoun" | "pron" => "PRP".to_string(),
         "modal" => "MD".to_string(),
         "to" => "TO".to_string(), // Special handling for infinitive 'to'?
+        "num" => "CD".to_string(),
         _ => "NN".to_string(),
     }
 }
 
 
-fn apply_context_rules(tokens: &mut [TaggedToken]) {
-    // Brill-style rules. We operate on PTB tags directly or mapped ones.
-    // transform_tag maps to: NN, VB, JJ, RB, IN, CC, DT, PRP, MD, TO
-    
-    let len = tokens.len();
-    if len < 2 { return; }
-
-    // We can't mutate while looking at neighbors easily with standard iter.
-    // Use indexing.
-    for i in 1..len {
-        let prev = tokens[i-1].tag.clone();
-        // let next = if i + 1 < len { Some(&tokens[i+1].tag) } else { None };
-        
-        let curr_word = tokens[i].word.to_lowercase();
-        // let curr_tag = tokens[i].tag.clone(); 
-        
-        // Rule 1: TO -> VB
-        // If previous is TO, current is likely VB (infinitive), unless it's a known noun?
-        // Our 'disambiguate' does this for ambig words, but what if it was guessed as NN?
-        if prev == "TO" {
-             // Force VB if it can be a verb ideally, but here we force statistically
-             tokens[i].tag = "VB".to_string();
-        }
-        
-        // Rule 2: MD -> VB
-        // Modal (can/will) -> Force VB (base form)
-        if prev == "MD" {
-            tokens[i].tag = "VB".to_string();
-        }
+/// Turn the rule tagger's coarse tags (NN, VB, JJ, RB, PRP, DT) into full
+/// Penn Treebank tags: NNS/NNP, VBD/VBN/VBG/VBZ/VBP, JJR/JJS, PRP$, WDT/WP/WRB.
+/// Readings that depend on the words around them ("her" PRP$, "there" EX,
+/// particles) are left to the learned rules.
+fn refine_tags(tokens: &mut [TaggedToken]) {
+    let first_word = tokens.iter().position(|t| t.is_word());
 
-        // Rule 3: DT -> NN/JJ
-        // If prev is DT, current should probably not be VB.
-        if prev == "DT" {
-            if tokens[i].tag == "VB" || tokens[i].tag == "VBP" { // If wrongly tagged as verb
-                 // Simple heuristic: change to NN. 
-                 // (Could be JJ, but NN is safer default after DT if V was wrong)
-                 tokens[i].tag = "NN".to_string();
-            }
+    for i in 0..tokens.len() {
+        if !tokens[i].is_word() || tokens[i].clitic.is_some() { continue; }
+        let lower = tokens[i].word.to_lowercase();
+
+        let tag = match lower.as_str() {
+            "to" => "TO",
+            "not" => "RB",
+            "who" | "whom" | "what" | "whoever" | "whatever" => "WP",
+            "whose" => "WP$",
+            "which" | "whichever" => "WDT",
+            "where" | "when" | "why" | "how" | "wherever" | "whenever" => "WRB",
+            "my" | "your" | "his" | "its" | "our" | "their" => "PRP$",
+            // Only coordinators are CC; "because", "while" etc. subordinate
+            _ if tokens[i].tag == "CC" && !matches!(lower.as_str(), "and" | "or" | "but" | "nor" | "yet" | "so" | "plus" | "either" | "neither" | "both") => "IN",
+            // "was sold", "has left" whatever the dictionary's first reading;
+            // after be/get a listed adjective stays one ("was tired") unless
+            // it can only be a participle ("was written")
+            _ if is_past_form(&lower) && match auxiliary_before(&tokens[..i]).as_deref() {
+                Some("have") => true,
+                Some(_) => tokens[i].tag != "JJ" || is_participle_only(&lower),
+                None => false,
+            } => "VBN",
+            _ => match tokens[i].tag.as_str() {
+                "NN" => noun_tag(&tokens[i].word, &lower, first_word == Some(i)),
+                "VB" => verb_tag(&lower, &tokens[..i], first_word == Some(i)),
+                "JJ" | "RB" => degree_tag(&lower, &tokens[i].tag),
+                _ => continue,
+            },
+        };
+        tokens[i].tag = tag.to_string();
+    }
+}
+
+/// Past forms that can't be a simple past: "gone", "done", "written", "shown".
+fn is_participle_only(lower: &str) -> bool {
+    is_past_form(lower) && (lower.ends_with("en") || lower.ends_with("wn") || matches!(lower, "done" | "gone" | "begun" | "sung" | "swum" | "drunk"))
+}
+
+/// Closest word before, skipping adverbs ("has never seen").
+fn previous_word(before: &[TaggedToken]) -> Option<&TaggedToken> {
+    before.iter().rev().find(|t| t.is_word() && !t.tag.starts_with("RB"))
+}
+
+/// "be", "have" or "get" when the previous word is a form of one of them.
+/// Clitics aren't resolved yet: "'s" stands for is/has, "'d" before a past
+/// form for had ("she's gone", "I'd seen").
+fn auxiliary_before(before: &[TaggedToken]) -> Option<String> {
+    before
+        .iter()
+        .rev()
+        .find(|t| (t.is_word() || t.kind == TokenKind::Clitic) && !t.tag.starts_with("RB"))
+        .map(|t| match t.kind {
+            TokenKind::Clitic if t.word.eq_ignore_ascii_case("'d") => "have".to_string(),
+            TokenKind::Clitic => "be".to_string(),
+            _ => lemmatize(&t.word, Some("VB")),
+        })
+        .filter(|lemma| matches!(lemma.as_str(), "be" | "have" | "get"))
+}
+
+fn noun_tag(word: &str, lower: &str, sentence_initial: bool) -> &'static str {
+    let plural = lemmatize(lower, Some("NNS")) != lower;
+    // At the start only known names count ("John", not "Books")
+    let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+    let proper = capitalized && (!sentence_initial || is_common_name(word) && DICT.lookup_all(lower).is_none());
+    match (proper, plural) {
+        (true, true) => "NNPS",
+        (true, false) => "NNP",
+        (false, true) => "NNS",
+        (false, false) => "NN",
+    }
+}
+
+/// Verb form from the ending and the words before it: "has gone" VBN,
+/// "went" VBD, "they go" VBP, "can go" VB.
+fn verb_tag(lower: &str, before: &[TaggedToken], sentence_initial: bool) -> &'static str {
+    match lower {
+        "be" => return "VB",
+        "am" | "are" => return "VBP",
+        "was" | "were" => return "VBD",
+        "been" => return "VBN",
+        "being" => return "VBG",
+        _ => {}
+    }
+
+    let prev = previous_word(before);
+    let prev_lemma = prev.map(|t| lemmatize(&t.word, Some("VB")));
+
+    if lower.ends_with("ing") && lower.len() > 4 {
+        return "VBG";
+    }
+    if is_past_form(lower) {
+        // After be/have/get refine_tags has already chosen VBN
+        return if is_participle_only(lower) { "VBN" } else { "VBD" };
+    }
+    if lower.ends_with('s') && lemmatize(lower, Some("VBZ")) != lower {
+        return "VBZ";
+    }
+
+    match prev {
+        _ if sentence_initial => "VB", // imperative
+        Some(p) if matches!(p.tag.as_str(), "MD" | "TO") => "VB",
+        Some(_) if prev_lemma.as_deref() == Some("do") => "VB",
+        Some(p) if p.tag == "PRP" || p.tag.starts_with("NN") || p.tag == "WDT" || p.tag == "WP" => "VBP",
+        _ => "VB",
+    }
+}
+
+/// Comparative and superlative adjectives and adverbs ("bigger", "best", "more").
+fn degree_tag(lower: &str, tag: &str) -> &'static str {
+    let adverb = tag == "RB";
+    let comparative = matches!(lower, "more" | "less" | "better" | "worse" | "further" | "farther")
+        || lower.ends_with("er") && is_graded_form(lower);
+    let superlative = matches!(lower, "most" | "least" | "best" | "worst" | "furthest" | "farthest")
+        || lower.ends_with("est") && is_graded_form(lower);
+    match (adverb, comparative, superlative) {
+        (false, true, _) => "JJR",
+        (false, _, true) => "JJS",
+        (false, _, _) => "JJ",
+        (true, true, _) => "RBR",
+        (true, _, true) => "RBS",
+        (true, _, _) => "RB",
+    }
+}
+
+/// Whether an -er/-est word is the inflection of a listed adjective ("bigger"
+/// from "big"), as opposed to "water" or "honest".
+fn is_graded_form(lower: &str) -> bool {
+    let lemma = lemmatize(lower, Some("JJR"));
+    lemma != lower && DICT.lookup_all(&lemma).is_some_and(|entries| entries.iter().any(|e| e.pos == "adj"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(text: &str, mode: TaggerMode) -> Vec<String> {
+        tag_sentence(&tokenize_sentence(text), mode).into_iter().map(|t| format!("{}/{}", t.word, t.tag)).collect()
+    }
+
+    fn tag_of(text: &str, word: &str) -> String {
+        tag_sentence(&tokenize_sentence(text), TaggerMode::default())
+            .into_iter()
+            .find(|t| t.word == word)
+            .map(|t| t.tag)
+            .unwrap_or_else(|| panic!("{} not in {:?}", word, text))
+    }
+
+    #[test]
+    fn the_hmm_tagger_is_the_default() {
+        assert_eq!(TaggerMode::default(), TaggerMode::Hmm);
+        assert_eq!(TaggerMode::from_name("HMM"), Some(TaggerMode::Hmm));
+        assert_eq!(TaggerMode::from_name("rules-base"), Some(TaggerMode::RulesBase));
+        assert_eq!(TaggerMode::from_name("crf"), None);
+    }
+
+    #[test]
+    fn common_words_get_their_usual_tags() {
+        assert_eq!(tag_of("It was a fair price.", "fair"), "JJ");
+        assert_eq!(tag_of("The fast train left.", "fast"), "JJ");
+        assert_eq!(tag_of("We went via Paris.", "via"), "IN");
+        assert_eq!(tag_of("Stop the car.", "Stop"), "VB");
+        assert_eq!(tag_of("Take a rest.", "rest"), "NN");
+        assert_eq!(tag_of("She sent a letter.", "sent"), "VBD");
+        assert_eq!(tag_of("I read while she sleeps.", "while"), "IN");
+        assert_eq!(tag_of("I read while she sleeps.", "sleeps"), "VBZ");
+        assert_eq!(tag_of("He waited a while.", "while"), "NN");
+        assert_eq!(tag_of("The baby is sleeping.", "sleeping"), "VBG");
+        assert_eq!(tag_of("In spite of the rain we left.", "spite"), "NN");
+        assert_eq!(tag_of("The stations are busy.", "stations"), "NNS");
+        assert_eq!(tag_of("I like ice cream.", "cream"), "NN");
+        assert_eq!(tag_of("It happened in 1990's London.", "1990's"), "CD");
+    }
+
+    #[test]
+    fn subordinators_are_in_and_coordinators_cc() {
+        assert_eq!(tags("I left because it rained and we got wet.", TaggerMode::default()), vec![
+            "I/PRP", "left/VBD", "because/IN", "it/PRP", "rained/VBD", "and/CC", "we/PRP", "got/VBD", "wet/JJ", "./.",
+        ]);
+    }
+
+    #[test]
+    fn every_mode_tags_every_token() {
+        for mode in [TaggerMode::Hmm, TaggerMode::Rules, TaggerMode::RulesBase] {
+            let tagged = tags("Mr. Brown's 3 dogs barked, didn't they?", mode);
+            assert_eq!(tagged.len(), tokenize_sentence("Mr. Brown's 3 dogs barked, didn't they?").len());
+            assert!(tagged.iter().all(|t| !t.ends_with('/')));
         }
-        
-        // Rule 4: "help" special case? "to help" -> help is VB. "a help" -> help is NN.
-        // Handled by above rules.
-        
-        // Rule 5: IN (Preposition) -> NN (Object) or VBG (Gerund)
-        // e.g. "by running" -> running is VBG (which we map to VB or keep VBG if we had it, but we map V->VB)
-        // If we have "by walk", "walk" is likely NN here? "by the walk".
-        // If "by walk" (grammatically poor but possible) -> NN.
-        if prev == "IN" {
-             if tokens[i].tag == "VB" {
-                  // "after run" -> run is NN.
-                  // "after eating" (eating is VB in our set) -> leave as VB (VBG)
-                  if !curr_word.ends_with("ing") {
-                      tokens[i].tag = "NN".to_string();
-                  }
-             }
+    }
+
+    #[test]
+    fn the_rule_tagger_emits_fine_grained_tags() {
+        assert_eq!(tags("There are bigger dogs in London than my best friend's.", TaggerMode::Rules)[..7], [
+            "There/EX", "are/VBP", "bigger/JJR", "dogs/NNS", "in/IN", "London/NNP", "than/IN",
+        ]);
+        assert_eq!(tag_of("My best friend came.", "best"), "JJS");
+        assert_eq!(tag_of("My best friend came.", "My"), "PRP$");
+        assert_eq!(tag_of("The book which I read was good.", "which"), "WDT");
+        assert_eq!(tags("The dog that bit him ran.", TaggerMode::Rules), vec!["The/DT", "dog/NN", "that/WDT", "bit/VBD", "him/PRP", "ran/VBD", "./."]);
+        assert_eq!(tag_of("Where do they live?", "Where"), "WRB");
+    }
+
+    #[test]
+    fn heldout_sentences_are_not_in_the_training_corpus() {
+        let sentences = |text: &'static str| text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let corpus: Vec<&str> = sentences(include_str!("../assets/pos_corpus.txt")).collect();
+        for line in sentences(include_str!("../assets/pos_heldout.txt")) {
+            assert!(!corpus.contains(&line), "held-out sentence in the corpus: {}", line);
         }
     }
-    
-    // Look ahead rules (Reverse loop or just index access)
-    for i in 0..len-1 {
-        // let curr = &tokens[i].tag;
-        let next_word = tokens[i+1].word.to_lowercase();
-        
-        // If current is NN but next is "the" -> likely VB?
-        // e.g. "book the flight" -> book is VB. 
-        if tokens[i].tag == "NN" && (next_word == "the" || next_word == "a" || next_word == "an") {
-             // Highly likely a verb
-             tokens[i].tag = "VB".to_string();
+
+    #[test]
+    fn the_shipped_rules_parse_and_generalize() {
+        let rules = parse_rules(include_str!("../assets/pos_rules.txt")).unwrap();
+        assert_eq!(rules.len(), RULES.len());
+        assert!(!rules.is_empty());
+
+        // Held-out sentences never reach either trainer
+        let heldout = include_str!("../assets/pos_heldout.txt");
+        let base = crate::evaluate_pos(heldout, TaggerMode::RulesBase).unwrap();
+        let learned = crate::evaluate_pos(heldout, TaggerMode::Rules).unwrap();
+        assert!(learned.accuracy > base.accuracy, "{} <= {}", learned.accuracy, base.accuracy);
+        // The default has to earn its place
+        let hmm = crate::evaluate_pos(heldout, TaggerMode::Hmm).unwrap();
+        assert!(hmm.accuracy > learned.accuracy, "{} <= {}", hmm.accuracy, learned.accuracy);
+    }
+
+    #[test]
+    fn like_after_a_subject_is_a_verb() {
+        // Nothing hand-written and no "whether ... like" sentence to learn
+        // from, so this has to generalize from other uses of "like"
+        let corpus = include_str!("../assets/pos_corpus.txt");
+        assert!(!corpus.lines().any(|l| l.to_lowercase().contains("whether/in") && l.contains("like/")));
+        assert_eq!(tag_of("Whether we like them or not, they stay.", "like"), "VBP");
+        assert_eq!(tag_of("If you like it, keep it.", "like"), "VBP");
+        // and the preposition stays one
+        assert_eq!(tag_of("She sings like a bird.", "like"), "IN");
+    }
+
+    #[test]
+    fn so_before_an_adjective_is_an_adverb() {
+        // None of these so + adjective pairs is in the training corpus
+        let corpus = include_str!("../assets/pos_corpus.txt");
+        for (text, pair) in [
+            ("It was so cold that we stayed inside.", ["so/RB", "cold/JJ"]),
+            ("I was so tired that I slept.", ["so/RB", "tired/JJ"]),
+            ("The music was so loud that we left.", ["so/RB", "loud/JJ"]),
+        ] {
+            assert!(!corpus.contains(&pair.join(" ")), "{} is in the corpus", pair.join(" "));
+            let tagged = tags(text, TaggerMode::default());
+            assert!(tagged.windows(2).any(|w| w == pair), "{:?}", tagged);
         }
     }
+
+    #[test]
+    fn a_reduced_relative_keeps_its_noun_and_verb() {
+        assert_eq!(tags("The man the police arrested was released.", TaggerMode::Rules)[..6], [
+            "The/DT", "man/NN", "the/DT", "police/NNS", "arrested/VBD", "was/VBD",
+        ]);
+    }
+
+    #[test]
+    fn verb_forms_follow_morphology_and_context() {
+        assert_eq!(tag_of("The car was sold yesterday.", "sold"), "VBN");
+        assert_eq!(tag_of("They sold the car.", "sold"), "VBD");
+        assert_eq!(tag_of("She has written a letter.", "written"), "VBN");
+        assert_eq!(tag_of("He is playing.", "playing"), "VBG");
+        assert_eq!(tag_of("He plays.", "plays"), "VBZ");
+        assert_eq!(tag_of("They play.", "play"), "VBP");
+        assert_eq!(tag_of("They can play.", "play"), "VB");
+        assert_eq!(tags("I can't swim.", TaggerMode::Rules), vec!["I/PRP", "can/MD", "not/RB", "swim/VB", "./."]);
+        assert_eq!(tag_of("Will you come?", "Will"), "MD");
+        assert_eq!(tag_of("She opened a can of beans.", "can"), "NN");
+    }
+
+    #[test]
+    fn opening_auxiliaries_and_particles() {
+        assert_eq!(tag_of("Do you know the way?", "Do"), "VBP");
+        assert_eq!(tag_of("Do it now!", "Do"), "VB");
+        assert_eq!(tag_of("She gave up smoking.", "up"), "RP");
+        assert_eq!(tag_of("He turned off the light.", "off"), "RP");
+        assert_ne!(tag_of("The sun is up.", "up"), "RP");
+    }
 }
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use serde::Serialize;
+use crate::corpus::parse_gold;
+use crate::pos::{tag_gold_words, TaggerMode};
+
+/// Tagging accuracy against a gold corpus.
+#[derive(Serialize, Debug)]
+pub struct PosEvaluation {
+    pub sentences: usize,
+    /// Gold tokens the tagger produced a token for
+    pub tokens: usize,
+    pub correct: usize,
+    pub accuracy: f64,
+    /// Gold tokens the tokenizer split differently; not scored
+    pub unaligned: usize,
+    /// Every tag seen in the gold data or the output, alphabetically
+    pub per_tag: Vec<TagScore>,
+    /// Gold tag -> predicted tag -> count, errors only
+    pub confusion: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Mis-tagged words, most errors first
+    pub word_errors: Vec<WordErrors>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TagScore {
+    pub tag: String,
+    pub precision: f64,
+    pub recall: f64,
+    /// Gold occurrences
+    pub support: usize,
+    /// Times the tagger output the tag
+    pub predicted: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WordErrors {
+    /// Lowercased
+    pub word: String,
+    pub errors: usize,
+    pub occurrences: usize,
+    /// (gold tag, predicted tag, count), most frequent first
+    pub confusions: Vec<(String, String, usize)>,
+}
+
+/// Run the tokenizer and tagger over a gold corpus (CoNLL-U or `word/TAG`
+/// lines) and score the tags.
+pub fn evaluate_pos(gold_text: &str, mode: TaggerMode) -> Result<PosEvaluation, String> {
+    let gold = parse_gold(gold_text)?;
+
+    let (mut tokens, mut correct, mut unaligned) = (0, 0, 0);
+    let mut gold_counts: HashMap<String, usize> = HashMap::new();
+    let mut predicted_counts: HashMap<String, usize> = HashMap::new();
+    let mut confusion: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut errors: HashMap<String, HashMap<(String, String), usize>> = HashMap::new();
+
+    for sentence in &gold {
+        let words: Vec<String> = sentence.iter().map(|(w, _)| w.clone()).collect();
+        for (prediction, (word, gold_tag)) in tag_gold_words(&words, mode).into_iter().zip(sentence) {
+            let Some(prediction) = prediction else {
+                unaligned += 1;
+                continue;
+            };
+            let word = word.to_lowercase();
+            tokens += 1;
+            *gold_counts.entry(gold_tag.clone()).or_default() += 1;
+            *predicted_counts.entry(prediction.tag.clone()).or_default() += 1;
+            *occurrences.entry(word.clone()).or_default() += 1;
+            if &prediction.tag == gold_tag {
+                correct += 1;
+            } else {
+                *confusion.entry(gold_tag.clone()).or_default().entry(prediction.tag.clone()).or_default() += 1;
+                *errors.entry(word).or_default().entry((gold_tag.clone(), prediction.tag)).or_default() += 1;
+            }
+        }
+    }
+
+    let tags: BTreeSet<&String> = gold_counts.keys().chain(predicted_counts.keys()).collect();
+    let per_tag = tags
+        .into_iter()
+        .map(|tag| {
+            let support = gold_counts.get(tag).copied().unwrap_or(0);
+            let predicted = predicted_counts.get(tag).copied().unwrap_or(0);
+            let mistaken_for: usize = confusion.get(tag).map_or(0, |row| row.values().sum());
+            let hits = support - mistaken_for;
+            TagScore {
+                tag: tag.clone(),
+                precision: ratio(hits, predicted),
+                recall: ratio(hits, support),
+                support,
+                predicted,
+            }
+        })
+        .collect();
+
+    let mut word_errors: Vec<WordErrors> = errors
+        .into_iter()
+        .map(|(word, pairs)| {
+            let mut confusions: Vec<(String, String, usize)> = pairs.into_iter().map(|((g, p), n)| (g, p, n)).collect();
+            confusions.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| (&a.0, &a.1).cmp(&(&b.0, &b.1))));
+            WordErrors {
+                errors: confusions.iter().map(|c| c.2).sum(),
+                occurrences: occurrences[&word],
+                word,
+                confusions,
+            }
+        })
+        .collect();
+    word_errors.sort_by(|a, b| b.errors.cmp(&a.errors).then_with(|| a.word.cmp(&b.word)));
+
+    Ok(PosEvaluation {
+        sentences: gold.len(),
+        tokens,
+        correct,
+        accuracy: ratio(correct, tokens),
+        unaligned,
+        per_tag,
+        confusion,
+        word_errors,
+    })
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_tags_and_lists_the_words_behind_errors() {
+        // "quickly" is deliberately mis-tagged in the gold data
+        let gold = "She/PRP ran/VBD quickly/JJ ./.\nI/PRP ca/MD n't/RB swim/VB ./.\n";
+        let eval = evaluate_pos(gold, TaggerMode::Rules).unwrap();
+        assert_eq!((eval.sentences, eval.tokens, eval.unaligned), (2, 9, 0));
+        assert_eq!(eval.correct, 8);
+        assert_eq!(eval.confusion["JJ"]["RB"], 1);
+
+        let jj = eval.per_tag.iter().find(|t| t.tag == "JJ").unwrap();
+        assert_eq!((jj.support, jj.predicted, jj.recall), (1, 0, 0.0));
+        let rb = eval.per_tag.iter().find(|t| t.tag == "RB").unwrap();
+        assert_eq!((rb.support, rb.predicted, rb.precision), (1, 2, 0.5));
+
+        assert_eq!(eval.word_errors.len(), 1);
+        assert_eq!(eval.word_errors[0].word, "quickly");
+        assert_eq!(eval.word_errors[0].confusions, vec![("JJ".to_string(), "RB".to_string(), 1)]);
+    }
+
+    #[test]
+    fn malformed_gold_is_an_error() {
+        assert!(evaluate_pos("She/PRP ran", TaggerMode::Rules).is_err());
+        assert_eq!(evaluate_pos("# nothing yet\n", TaggerMode::Rules).unwrap().accuracy, 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+use std::ops::Range;
+use phf::phf_set;
+
+/// Abbreviations that are practically never the last word of a sentence
+/// ("Mr. Brown", "e.g. apples"), compared lowercased without the final period.
+static NON_FINAL_ABBREVIATIONS: phf::Set<&'static str> = phf_set! {
+    "mr", "mrs", "ms", "dr", "prof", "st", "mt", "capt", "gen", "sen", "rep", "gov", "lt", "col", "sgt",
+    "rev", "hon", "fr", "e.g", "i.e", "cf", "vs", "viz", "vol", "pp", "fig", "approx",
+};
+
+/// Abbreviations that may end a sentence; they do when the next word is capitalized.
+static FINAL_ABBREVIATIONS: phf::Set<&'static str> = phf_set! {
+    "etc", "inc", "ltd", "co", "corp", "jr", "sr", "u.s", "u.k", "a.m", "p.m", "esp", "al",
+};
+
+/// Sentence-final marks; runs such as "?!" or "..." end a sentence together.
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '\u{2026}')
+}
+
+/// Closing quotes and brackets that belong to the sentence they follow.
+fn is_closer(c: char) -> bool {
+    matches!(c, '"' | '\'' | '\u{201D}' | '\u{2019}' | ')' | ']' | '}')
+}
+
+/// Split text into sentences, returned as byte spans into `text` with
+/// surrounding whitespace trimmed. Terminal punctuation and any closing
+/// quotes or brackets after it stay with their sentence. A period is not a
+/// boundary inside a number ("3.50"), after an initial ("J. K. Rowling") or
+/// after a known abbreviation ("Mr. Brown"). A blank line always ends a
+/// sentence, so headings without punctuation stand alone.
+pub fn split_sentences(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+
+        // Paragraph break
+        if c == '\n' {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j].1.is_whitespace() && chars[j].1 != '\n' {
+                j += 1;
+            }
+            if j < chars.len() && chars[j].1 == '\n' {
+                push_span(text, start..pos, &mut spans);
+                start = chars[j].0;
+                i = j + 1;
+                continue;
+            }
+        }
+
+        if !is_terminator(c) {
+            i += 1;
+            continue;
+        }
+
+        // Terminator run, then closing quotes/brackets
+        let run_start = i;
+        while i < chars.len() && is_terminator(chars[i].1) {
+            i += 1;
+        }
+        let run_len = i - run_start;
+        while i < chars.len() && is_closer(chars[i].1) {
+            i += 1;
+        }
+        let end = chars.get(i).map_or(text.len(), |&(p, _)| p);
+
+        // Must be followed by whitespace or the end of the text ("3.50", "U.S.A")
+        if chars.get(i).is_some_and(|&(_, next)| !next.is_whitespace()) {
+            continue;
+        }
+
+        // The next sentence doesn't start in lowercase ("e.g. apples", "he asked why? and left")
+        let next_visible = chars[i..].iter().map(|&(_, c)| c).find(|c| !c.is_whitespace() && !is_opener(*c));
+        if next_visible.is_some_and(|c| c.is_lowercase()) {
+            continue;
+        }
+
+        if run_len == 1 && c == '.' {
+            let word = word_before(text, pos);
+            let lower = word.to_lowercase();
+            // A lone capital, except the pronoun: "Neither do I. She left."
+            let is_initial = word.chars().count() == 1 && word.chars().all(|c| c.is_uppercase()) && word != "I";
+            if is_initial || NON_FINAL_ABBREVIATIONS.contains(lower.as_str()) {
+                continue;
+            }
+            // "No. 5", but not "He said no."
+            if matches!(lower.as_str(), "no" | "nos") && next_visible.is_some_and(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if FINAL_ABBREVIATIONS.contains(lower.as_str())
+                && !next_visible.is_some_and(|c| c.is_uppercase())
+            {
+                continue;
+            }
+        }
+
+        push_span(text, start..end, &mut spans);
+        start = end;
+    }
+
+    push_span(text, start..text.len(), &mut spans);
+    spans
+}
+
+fn is_opener(c: char) -> bool {
+    matches!(c, '"' | '\'' | '\u{201C}' | '\u{2018}' | '(' | '[' | '{')
+}
+
+/// The word ending right before byte `pos`, internal periods included ("e.g").
+fn word_before(text: &str, pos: usize) -> &str {
+    let before = &text[..pos];
+    let start = before
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '.'))
+        .map_or(0, |i| i + before[i..].chars().next().map_or(1, char::len_utf8));
+    &before[start..]
+}
+
+/// Trim whitespace and keep the span if it contains any word characters.
+fn push_span(text: &str, range: Range<usize>, spans: &mut Vec<Range<usize>>) {
+    let slice = &text[range.clone()];
+    if !slice.chars().any(char::is_alphanumeric) {
+        return;
+    }
+    let lead = slice.len() - slice.trim_start().len();
+    let trail = slice.len() - slice.trim_end().len();
+    spans.push(range.start + lead..range.end - trail);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<&str> {
+        split_sentences(text).into_iter().map(|span| &text[span]).collect()
+    }
+
+    #[test]
+    fn abbreviations_initials_and_decimals_do_not_end_sentences() {
+        assert_eq!(sentences("Mr. Brown paid $3.50. He left."), vec!["Mr. Brown paid $3.50.", "He left."]);
+        assert_eq!(sentences("J. K. Rowling wrote it. Fans cheered."), vec!["J. K. Rowling wrote it.", "Fans cheered."]);
+        assert_eq!(sentences("Neither do I. She left."), vec!["Neither do I.", "She left."]);
+        assert_eq!(sentences("Buy fruit, e.g. apples. Then rest."), vec!["Buy fruit, e.g. apples.", "Then rest."]);
+    }
+
+    #[test]
+    fn final_abbreviations_end_a_sentence_before_a_capital() {
+        assert_eq!(sentences("Bring pens, paper etc. and a bag."), vec!["Bring pens, paper etc. and a bag."]);
+        assert_eq!(sentences("Bring pens, paper etc. The rest is here."), vec!["Bring pens, paper etc.", "The rest is here."]);
+    }
+
+    #[test]
+    fn closing_quotes_and_mark_runs_stay_with_their_sentence() {
+        assert_eq!(sentences("\"Stop!\" she said. Really?! Yes..."), vec!["\"Stop!\" she said.", "Really?!", "Yes..."]);
+        assert_eq!(sentences("(It was late.) We slept."), vec!["(It was late.)", "We slept."]);
+    }
+
+    #[test]
+    fn spans_are_trimmed_and_blank_lines_split() {
+        let text = "  Chapter One\n\nIt began.  ";
+        assert_eq!(split_sentences(text), vec![2..13, 15..24]);
+        assert_eq!(sentences(text), vec!["Chapter One", "It began."]);
+        assert!(split_sentences(" ... ").is_empty());
+    }
+}
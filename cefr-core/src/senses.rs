@@ -0,0 +1,161 @@
+//! Picks the sense of a polysemous word from its sentence, so "run fast"
+//! scores as A1 but "run a company" as B1.
+//!
+//! Senses come from assets/senses.csv via `WordEntry.senses`. Each sense of
+//! the token's POS is scored on its collocates within a few words either side
+//! and, for verbs, on whether the object that follows is the expected kind.
+//! The highest score wins; ties go to the earlier (default) sense.
+
+use std::collections::HashSet;
+use crate::dictionary::{pos_for_tag, tag_fits_pos, ObjectType, Sense, WordEntry, DICT};
+use crate::discourse::is_common_name;
+use crate::lemmatizer::lemmatize;
+use crate::pos::TaggedToken;
+
+/// Words either side searched for collocates
+const WINDOW: usize = 4;
+const COLLOCATE_WEIGHT: i32 = 2;
+const OBJECT_MATCH_WEIGHT: i32 = 1;
+const OBJECT_MISMATCH_WEIGHT: i32 = -1;
+
+/// Common nouns for people, so "train staff" reads as a person object.
+const PERSON_NOUNS: &[&str] = &[
+    "people", "person", "man", "woman", "child", "boy", "girl", "friend", "family", "team", "staff",
+    "student", "teacher", "customer", "client", "worker", "employee", "player", "audience", "parent",
+    "mother", "father", "mum", "dad", "brother", "sister", "baby", "doctor", "colleague", "guest",
+];
+
+/// Nouns that head a time adverbial rather than an object ("run every morning").
+const TIME_NOUNS: &[&str] = &[
+    "morning", "afternoon", "evening", "night", "day", "week", "weekend", "month", "year", "today",
+    "tomorrow", "yesterday", "tonight",
+];
+
+/// The sense of `tokens[index]` best supported by its context, or `None`
+/// when the entry lists no sense for the token's POS.
+pub fn select_sense<'a>(entry: &'a WordEntry, tokens: &[TaggedToken], index: usize) -> Option<&'a Sense> {
+    let token = &tokens[index];
+    let pos = pos_for_tag(&token.tag);
+    let candidates = senses_of(entry, token).iter().filter(|s| s.pos.is_empty() || tag_fits_pos(&token.tag, s.pos));
+
+    let context = context_words(tokens, index);
+    let object = if pos == "verb" { Some(object_type(tokens, index)) } else { None };
+
+    let mut best: Option<(&Sense, i32)> = None;
+    for sense in candidates {
+        let mut score = sense.collocates.iter().filter(|c| context.contains(**c)).count() as i32 * COLLOCATE_WEIGHT;
+        if let (Some((found, head)), expected) = (&object, sense.object) {
+            // A collocate in object position is a cue, not an object ("get home")
+            let head_is_cue = head.as_deref().is_some_and(|h| sense.collocates.contains(&h));
+            if expected != ObjectType::Any && !head_is_cue {
+                score += if *found == expected { OBJECT_MATCH_WEIGHT } else { OBJECT_MISMATCH_WEIGHT };
+            }
+        }
+        if best.is_none_or(|(_, top)| score > top) {
+            best = Some((sense, score));
+        }
+    }
+    best.map(|(sense, _)| sense)
+}
+
+/// The senses for `token`. Core-list rows for inflected forms ("had", "has")
+/// are their own lemma and carry none, so those borrow the senses of the
+/// lemma's entry.
+fn senses_of(entry: &WordEntry, token: &TaggedToken) -> &'static [Sense] {
+    if !entry.senses.is_empty() {
+        return entry.senses;
+    }
+    let lemma = lemmatize(&token.word, Some(&token.tag)).to_lowercase();
+    if lemma == entry.lemma {
+        return &[];
+    }
+    DICT.lookup_all(&lemma)
+        .and_then(|entries| entries.iter().find(|e| e.lemma == lemma && !e.senses.is_empty()))
+        .map_or(&[], |e| e.senses)
+}
+
+/// Lowercased forms and lemmas of the words near `index`, excluding it.
+fn context_words(tokens: &[TaggedToken], index: usize) -> HashSet<String> {
+    let start = index.saturating_sub(WINDOW);
+    let end = (index + WINDOW + 1).min(tokens.len());
+    (start..end)
+        .filter(|&i| i != index && tokens[i].is_word())
+        .flat_map(|i| {
+            let t = &tokens[i];
+            [t.word.to_lowercase(), lemmatize(&t.word, Some(&t.tag)).to_lowercase()]
+        })
+        .collect()
+}
+
+/// The kind of object after the verb at `index` and the lemma of its head:
+/// the head of a following noun phrase, or `None` when the clause continues
+/// with anything else or the noun phrase is a time adverbial.
+fn object_type(tokens: &[TaggedToken], index: usize) -> (ObjectType, Option<String>) {
+    for t in &tokens[index + 1..] {
+        if !t.is_word() {
+            break;
+        }
+        match t.tag.as_str() {
+            "DT" | "PDT" | "PRP$" | "CD" | "POS" => continue,
+            tag if tag.starts_with("JJ") => continue,
+            "PRP" if t.word.eq_ignore_ascii_case("it") => return (ObjectType::Thing, None),
+            "PRP" => return (ObjectType::Person, None),
+            "NNP" | "NNPS" if is_common_name(&t.word) => return (ObjectType::Person, None),
+            tag if tag.starts_with("NN") => {
+                let lemma = lemmatize(&t.word, Some(tag)).to_lowercase();
+                let kind = if TIME_NOUNS.contains(&lemma.as_str()) {
+                    ObjectType::None
+                } else if PERSON_NOUNS.contains(&lemma.as_str()) {
+                    ObjectType::Person
+                } else {
+                    ObjectType::Thing
+                };
+                return (kind, Some(lemma));
+            }
+            _ => break,
+        }
+    }
+    (ObjectType::None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::CEFRLevel;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    /// Gloss and level of the sense chosen for `word` in `text`.
+    fn sense(text: &str, word: &str) -> Option<(&'static str, CEFRLevel)> {
+        let tokens = tag_sentence(&tokenize_sentence(text), TaggerMode::default());
+        let index = tokens.iter().position(|t| t.word == word).unwrap_or_else(|| panic!("{} not in {:?}", word, text));
+        let entry = DICT.lookup(word, Some(&tokens[index].tag))?;
+        select_sense(entry, &tokens, index).map(|s| (s.gloss, s.level.clone()))
+    }
+
+    #[test]
+    fn collocates_and_objects_pick_the_sense() {
+        assert_eq!(sense("She can run fast.", "run"), Some(("move fast on foot", CEFRLevel::A1)));
+        assert_eq!(sense("He runs a small hotel.", "runs"), Some(("manage a business or activity", CEFRLevel::B1)));
+        assert_eq!(sense("The engine runs smoothly.", "runs"), Some(("operate or function", CEFRLevel::B1)));
+        assert_eq!(sense("He made me laugh.", "made"), Some(("cause someone to do or be", CEFRLevel::B1)));
+        assert_eq!(sense("Take a short break.", "break"), Some(("short rest", CEFRLevel::A2)));
+    }
+
+    #[test]
+    fn time_adverbials_and_collocates_are_not_objects() {
+        assert_eq!(sense("I run every morning.", "run").map(|s| s.0), Some("move fast on foot"));
+        assert_eq!(sense("We got home late.", "got").map(|s| s.0), Some("arrive"));
+    }
+
+    #[test]
+    fn inflected_core_rows_use_their_lemmas_senses() {
+        assert_eq!(sense("We had fun.", "had").map(|s| s.0), Some("experience"));
+        assert_eq!(sense("She has breakfast early.", "has").map(|s| s.0), Some("eat or drink"));
+    }
+
+    #[test]
+    fn single_level_words_have_no_sense() {
+        assert_eq!(sense("The cat slept.", "cat"), None);
+    }
+}
@@ -1,311 +1,246 @@
-use regex::Regex;
-use lazy_static::lazy_static;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use crate::offsets::Offsets;
+use crate::parser::{parse_sentence, ClauseKind, ClauseNode, Node};
 use crate::pos::TaggedToken;
+use crate::verb_groups::{chunk_verb_groups, Voice};
 
 #[derive(Serialize, Default, Debug)]
 pub struct SyntaxMetrics {
     pub clause_density: f64,
     pub passive_ratio: f64,
+    /// Mean over sentences of their maximum clause depth (main clause = 1)
     pub average_tree_depth: f64,
+    pub max_tree_depth: usize,
+    /// Mean clause depth of words over the whole text
+    pub mean_clause_depth: f64,
+    /// Maximum depth -> number of sentences
+    pub depth_histogram: BTreeMap<usize, usize>,
+    pub sentence_depths: Vec<SentenceDepth>,
+    /// Subordinate clauses by type: "relative", "adverbial", "complement"
+    pub clause_types: BTreeMap<String, usize>,
+    pub subordinate_clauses: Vec<SubordinateClause>,
 }
 
-/// Clause types for stack-based nesting detection
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum ClauseType {
-    Explicit,   // who, which, that, because, if, etc.
-    Implicit,   // Zero-pronoun: "The book I read"
-    Reduced,    // Participle: "The man standing there"
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct SentenceDepth {
+    pub max: usize,
+    /// Mean depth of the sentence's words
+    pub mean: f64,
 }
 
-pub struct SyntacticAnalyzer;
-
-lazy_static! {
-    // Coordinating conjunctions (low complexity impact)
-    static ref COORDINATING_CONJUNCTIONS: Regex = Regex::new(r"(?i)\b(and|but|or|so)\b").unwrap();
-    
-    // Irregular past participles for passive voice and reduced clause detection
-    static ref IRREGULAR_PAST_PARTICIPLES: HashSet<&'static str> = {
-        let mut s = HashSet::new();
-        let words = vec![
-            "sold", "told", "made", "built", "left", "kept", "slept", "felt", "dealt", "meant",
-            "broken", "chosen", "frozen", "spoken", "stolen", "woken", "woven", "written",
-            "driven", "ridden", "risen", "bitten", "hidden", "eaten", "beaten", "fallen",
-            "seen", "known", "thrown", "flown", "grown", "shown", "drawn", "gone", "done",
-            "found", "bought", "caught", "taught", "thought", "brought", "fought", "said", "paid", "laid",
-            "heard", "read", "cut", "put", "set", "hit", "let", "cost", "hurt", "shut", "quit"
-        ];
-        for w in words {
-            s.insert(w);
-        }
-        s
-    };
-    
-    // Subject pronouns for implicit clause detection
-    static ref SUBJECT_PRONOUNS: HashSet<&'static str> = {
-        let mut s = HashSet::new();
-        for w in &["i", "you", "he", "she", "it", "we", "they"] {
-            s.insert(*w);
-        }
-        s
-    };
+#[derive(Serialize, Debug)]
+pub struct SubordinateClause {
+    pub sentence: usize,
+    pub kind: ClauseKind,
+    /// The subordinator or relative word; `None` for zero and reduced relatives
+    pub opener: Option<String>,
+    /// From the opener (or first word) to the clause's last word
+    pub offsets: Offsets,
 }
 
+pub struct SyntacticAnalyzer;
+
 impl SyntacticAnalyzer {
-    pub fn analyze(text: &str, sentences: &[Vec<TaggedToken>]) -> SyntaxMetrics {
+    /// `text` is what the tokens' spans index; `to_offsets` maps its byte
+    /// ranges into the result's offsets. Clauses come from the parser's
+    /// clause tree (`parser::parse_sentence`), so the metrics describe the
+    /// same clauses the parse exposes.
+    pub fn analyze(text: &str, sentences: &[Vec<TaggedToken>], to_offsets: impl Fn(Range<usize>) -> Offsets) -> SyntaxMetrics {
         let num_sentences = sentences.len() as f64;
         if num_sentences == 0.0 { return SyntaxMetrics::default(); }
 
-        // Calculate clause density: count subordinating + coordinating conjunctions
-        let sub_count = Self::count_subordinating_conjunctions(sentences);
-        let coord_count = COORDINATING_CONJUNCTIONS.find_iter(text).count() as f64;
-        
-        let clauses_count = sub_count + coord_count;
+        let mut sentence_depths = Vec::new();
+        let mut subordinate_clauses = Vec::new();
+        let mut clause_types = BTreeMap::new();
+        let mut clauses_count = 0.0;
+        for (s, sent) in sentences.iter().enumerate() {
+            let mains = parse_sentence(sent, text, &to_offsets);
+            // Each main clause after the first was joined on ("..., and she left")
+            clauses_count += mains.len().saturating_sub(1) as f64;
+            let mut depths = Vec::new();
+            let mut found = Vec::new();
+            for main in &mains {
+                Self::visit(main, 1, sent, &mut depths, &mut found);
+            }
+            sentence_depths.push(SentenceDepth {
+                max: depths.iter().copied().max().unwrap_or(1),
+                mean: if depths.is_empty() { 1.0 } else { depths.iter().sum::<usize>() as f64 / depths.len() as f64 },
+            });
+            for clause in found {
+                // Clause density counts the clauses opened by a subordinator
+                if clause.opener.is_some() {
+                    clauses_count += 1.0;
+                }
+                *clause_types.entry(clause.kind.name().to_string()).or_default() += 1;
+                subordinate_clauses.push(SubordinateClause {
+                    sentence: s,
+                    kind: clause.kind,
+                    opener: clause.opener.map(|i| sent[i].word.to_lowercase()),
+                    offsets: clause.offsets,
+                });
+            }
+        }
         let clause_density = clauses_count / num_sentences;
 
         let passive_count = Self::count_passives(sentences);
         let passive_ratio = passive_count as f64 / num_sentences;
 
-        let average_tree_depth = Self::calculate_average_depth(sentences);
+        let mut depth_histogram = BTreeMap::new();
+        for depth in &sentence_depths {
+            *depth_histogram.entry(depth.max).or_default() += 1;
+        }
+        let average_tree_depth = sentence_depths.iter().map(|d| d.max as f64).sum::<f64>() / num_sentences;
+        let max_tree_depth = sentence_depths.iter().map(|d| d.max).max().unwrap_or(1);
+        let word_count = sentences.iter().map(|sent| sent.iter().filter(|t| t.is_word()).count()).sum::<usize>();
+        let mean_clause_depth = if word_count > 0 {
+            sentences.iter().zip(&sentence_depths)
+                .map(|(sent, depth)| depth.mean * sent.iter().filter(|t| t.is_word()).count() as f64)
+                .sum::<f64>() / word_count as f64
+        } else {
+            1.0
+        };
 
         SyntaxMetrics {
             clause_density,
             passive_ratio,
             average_tree_depth,
+            max_tree_depth,
+            mean_clause_depth,
+            depth_histogram,
+            sentence_depths,
+            clause_types,
+            subordinate_clauses,
         }
     }
     
-    /// Count subordinating conjunctions from tagged tokens
-    fn count_subordinating_conjunctions(sentences: &[Vec<TaggedToken>]) -> f64 {
-        let mut count = 0;
-        for sent in sentences {
-            for token in sent {
-                if Self::is_subordinating_conjunction(&token.word) {
-                    count += 1;
-                }
-            }
-        }
-        count as f64
-    }
-
+    /// Passive verb groups ("was written", "should have been told")
     fn count_passives(sentences: &[Vec<TaggedToken>]) -> usize {
-        let mut count = 0;
-        for sent in sentences {
-            let mut i = 0;
-            while i < sent.len() {
-                // Check for 'be' verb
-                if Self::is_be_verb(&sent[i].word) {
-                    // Look ahead 1 or 2 tokens
-                    let mut found = false;
-                    // Check next (i+1)
-                    if i + 1 < sent.len() {
-                        if Self::is_past_participle(&sent[i+1].word, &sent[i+1].tag) {
-                            found = true;
-                        } 
-                        // Allow 1 adverb in between (i+2), e.g. "was 'always' done"
-                        else if (sent[i+1].tag == "RB" || sent[i+1].word.ends_with("ly")) && i + 2 < sent.len() {
-                             if Self::is_past_participle(&sent[i+2].word, &sent[i+2].tag) {
-                                found = true;
-                            }
-                        }
-                    }
-                    
-                    if found {
-                        count += 1;
-                        i += 1; // Advance to avoid double counting overlap
-                    }
-                }
-                i += 1;
-            }
-        }
-        count
-    }
-    
-    fn is_be_verb(word: &str) -> bool {
-        matches!(word.to_lowercase().as_str(), "am" | "is" | "are" | "was" | "were" | "be" | "been" | "being" | "'m" | "'re" | "'s")
+        sentences
+            .iter()
+            .flat_map(|sent| chunk_verb_groups(sent))
+            .filter(|group| group.voice == Voice::Passive)
+            .count()
     }
     
-    /// Enhanced past participle detection with POS tag awareness
-    fn is_past_participle(word: &str, tag: &str) -> bool {
-        let lower = word.to_lowercase();
-        
-        // 1. Check irregular list first (most reliable)
-        if IRREGULAR_PAST_PARTICIPLES.contains(lower.as_str()) {
-            return true;
+    /// Walk `clause`, at nesting `depth` (main clause = 1): record each
+    /// word's depth and collect the subordinate clauses, outermost first.
+    /// Clauses in sequence ("who ..., which ...") share a depth; nested
+    /// ones ("who ... that ...") add one each.
+    fn visit<'a>(clause: &'a ClauseNode, depth: usize, sent: &[TaggedToken], depths: &mut Vec<usize>, found: &mut Vec<&'a ClauseNode>) {
+        if clause.kind != ClauseKind::Main {
+            found.push(clause);
         }
-        
-        // 2. Check suffix with POS tag validation to avoid false positives
-        // VBN = Past Participle, VBG = Gerund, JJ = Adjective (can be participial)
-        let is_verb_or_adj_tag = tag.starts_with("VB") || tag.starts_with("JJ");
-        
-        if is_verb_or_adj_tag {
-            // Check -ed suffix (but exclude short words like "bed", "red", "fed")
-            if lower.ends_with("ed") && lower.len() > 3 {
-                return true;
-            }
-            // Check -en suffix (written, broken) - also exclude short words
-            if lower.ends_with("en") && lower.len() > 4 {
-                return true;
+        for child in &clause.children {
+            match child {
+                Node::Clause(c) => Self::visit(c, depth + 1, sent, depths, found),
+                Node::Chunk(c) => depths.extend((c.start..c.end).filter(|&i| sent[i].is_word()).map(|_| depth)),
+                Node::Token(t) => depths.extend(sent[t.index].is_word().then_some(depth)),
             }
         }
-        
-        false
     }
-    
-    // ==================== CLAUSE DEPTH ANALYSIS ====================
-    
-    /// Calculate average clause nesting depth using stack-based detection
-    /// Fixes: zero-pronoun clauses, reduced relative clauses, comma ambiguity
-    fn calculate_average_depth(sentences: &[Vec<TaggedToken>]) -> f64 {
-        let mut total_max_depth = 0.0;
-        
-        for sent in sentences {
-            let mut stack: Vec<ClauseType> = Vec::new();
-            let mut max_depth: usize = 1; // Root clause = depth 1
-            
-            for i in 0..sent.len() {
-                let token = &sent[i];
-                let prev = if i > 0 { Some(&sent[i-1]) } else { None };
-                let next = if i + 1 < sent.len() { Some(&sent[i+1]) } else { None };
-                
-                // ============ PUSH CONDITIONS (Enter Clause) ============
-                
-                // 1. Explicit subordinating conjunction: who, which, that, because, etc.
-                if Self::is_subordinating_conjunction(&token.word) {
-                    stack.push(ClauseType::Explicit);
-                    max_depth = max_depth.max(stack.len() + 1);
-                    continue;
-                }
-                
-                // 2. Implicit zero-pronoun clause: Noun + Subject Pronoun
-                //    e.g., "The book I read" → "book" (NN) + "I" (PRP)
-                if let Some(prev_tok) = prev {
-                    if Self::is_noun_tag(&prev_tok.tag) && Self::is_subject_pronoun(&token.word) {
-                        // Don't push if prev was a comma (likely enumeration or explicit clause ended)
-                        if prev_tok.word != "," {
-                            stack.push(ClauseType::Implicit);
-                            max_depth = max_depth.max(stack.len() + 1);
-                            continue;
-                        }
-                    }
-                }
-                
-                // 3. Reduced relative clause: Noun + V-ing / V-ed (participle)
-                //    e.g., "The man standing there", "The book written by him"
-                if let Some(prev_tok) = prev {
-                    if Self::is_noun_tag(&prev_tok.tag) && Self::is_participle_for_clause(&token.word, &token.tag) {
-                        stack.push(ClauseType::Reduced);
-                        max_depth = max_depth.max(stack.len() + 1);
-                        continue;
-                    }
-                }
-                
-                // ============ POP CONDITIONS (Exit Clause) ============
-                
-                // Smart comma handling
-                if token.word == "," {
-                    // Check if this is an enumeration comma (followed by and/or or adjective)
-                    let is_enumeration = if let Some(next_tok) = next {
-                        Self::is_enumeration_context(&next_tok.word, &next_tok.tag)
-                    } else {
-                        false
-                    };
-                    
-                    if !is_enumeration && !stack.is_empty() {
-                        // Pop the stack - comma can close any clause type
-                        // For Explicit clauses (non-restrictive): comma often marks boundary
-                        // For Implicit/Reduced: comma almost always ends them
-                        stack.pop();
-                    }
-                    continue;
-                }
-                
-                // Period, semicolon, question mark: clear all clauses
-                if token.word == "." || token.word == ";" || token.word == "?" || token.word == "!" {
-                    stack.clear();
-                    continue;
-                }
-                
-                // Main verb detection: can close implicit/reduced clauses
-                // If we see a finite verb after an implicit clause, it might be the main clause verb
-                if Self::is_main_clause_verb_signal(&token.word, &token.tag) {
-                    // Pop only Implicit/Reduced, not Explicit
-                    if let Some(clause_type) = stack.last() {
-                        if *clause_type != ClauseType::Explicit {
-                            // Could be end of implicit clause - conservative approach
-                        }
-                    }
-                }
-            }
-            
-            total_max_depth += max_depth as f64;
-        }
-        
-        total_max_depth / (sentences.len() as f64).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::segmenter::split_sentences;
+    use crate::tokenizer::tokenize_sentence;
+
+    fn tagged(text: &str) -> Vec<TaggedToken> {
+        tag_sentence(&tokenize_sentence(text), TaggerMode::default())
     }
-    
-    // ==================== HELPER FUNCTIONS ====================
-    
-    /// Check if word is a subordinating conjunction or relative pronoun
-    fn is_subordinating_conjunction(word: &str) -> bool {
-        matches!(word.to_lowercase().as_str(),
-            "who" | "which" | "that" | "whom" | "whose" | "where" | "when" |
-            "because" | "although" | "though" | "if" | "after" | "before" |
-            "since" | "unless" | "until" | "while" | "whereas" | "whether"
-        )
+
+    fn to_offsets(r: Range<usize>) -> Offsets {
+        Offsets { start: r.start, end: r.end, start_utf16: r.start, end_utf16: r.end }
     }
-    
-    /// Check if POS tag indicates a noun
-    fn is_noun_tag(tag: &str) -> bool {
-        tag.starts_with("NN")  // NN, NNS, NNP, NNPS
+
+    /// Maximum depth and the subordinate clauses as "kind: words", outermost first.
+    fn clauses(text: &str) -> (usize, Vec<String>) {
+        let metrics = SyntacticAnalyzer::analyze(text, &[tagged(text)], to_offsets);
+        let found = metrics.subordinate_clauses.iter().map(|c| format!("{}: {}", c.kind.name(), &text[c.offsets.start..c.offsets.end]));
+        (metrics.max_tree_depth, found.collect())
     }
-    
-    /// Check if word is a subject pronoun (can start implicit clause)
-    fn is_subject_pronoun(word: &str) -> bool {
-        SUBJECT_PRONOUNS.contains(word.to_lowercase().as_str())
+
+    #[test]
+    fn sequence_and_nesting_differ_in_depth() {
+        let (depth, found) = clauses("The man who lives next door, who works at the bank, which is closed, is nice.");
+        assert_eq!(depth, 2);
+        assert_eq!(found, ["relative: who lives next door", "relative: who works at the bank", "relative: which is closed"]);
+        let (depth, found) = clauses("The man who owns the dog that bit the boy who cried is here.");
+        assert_eq!(depth, 4);
+        assert_eq!(found, ["relative: who owns the dog that bit the boy who cried", "relative: that bit the boy who cried", "relative: who cried"]);
     }
-    
-    /// Enhanced participle detection for reduced clauses (stricter than passive detection)
-    fn is_participle_for_clause(word: &str, tag: &str) -> bool {
-        let lower = word.to_lowercase();
-        
-        // Must have verb-like POS tag
-        let is_verb_tag = tag.starts_with("VB") || tag == "JJ";
-        if !is_verb_tag {
-            return false;
-        }
-        
-        // Check irregular participles
-        if IRREGULAR_PAST_PARTICIPLES.contains(lower.as_str()) {
-            return true;
-        }
-        
-        // Check -ing (present participle/gerund)
-        if lower.ends_with("ing") && lower.len() > 4 {
-            return true;
-        }
-        
-        // Check -ed (past participle) - exclude short words
-        if lower.ends_with("ed") && lower.len() > 4 {
-            return true;
+
+    #[test]
+    fn clauses_close_on_verbs_commas_and_coordinators() {
+        assert_eq!(clauses("If it rains, we will stay home."), (2, vec!["adverbial: If it rains".to_string()]));
+        assert_eq!(clauses("If it rains it will flood."), (2, vec!["adverbial: If it rains".to_string()]));
+        assert_eq!(clauses("She left because she was tired and he stayed."), (2, vec!["adverbial: because she was tired".to_string()]));
+        let (depth, found) = clauses("Although he was tired, he kept working because the job that he loved was important.");
+        assert_eq!(depth, 3);
+        assert_eq!(found, ["adverbial: Although he was tired", "adverbial: because the job that he loved was important", "relative: that he loved"]);
+    }
+
+    #[test]
+    fn clauses_without_an_opener() {
+        assert_eq!(clauses("The book I read was long."), (2, vec!["relative: I read".to_string()]));
+        assert_eq!(clauses("The man the police arrested was released."), (2, vec!["relative: the police arrested".to_string()]));
+        assert_eq!(clauses("The man standing there is my father."), (2, vec!["relative: standing there".to_string()]));
+        assert_eq!(clauses("She said the shop was closed."), (2, vec!["complement: the shop was closed".to_string()]));
+        assert_eq!(clauses("I told the man the police arrived."), (1, vec![]));
+        assert_eq!(clauses("I know the answer."), (1, vec![]));
+        let (depth, _) = clauses("He said he would come when he finished the work that he had started.");
+        assert_eq!(depth, 4);
+    }
+
+    #[test]
+    fn only_clause_openers_count() {
+        for text in ["I like that book.", "That is true.", "When?", "After lunch we went home.", "After that, we left.",
+            "I have lived here since 2010.", "I was there for a while.", "Where do you live?", "I don't know when."] {
+            assert_eq!(clauses(text), (1, vec![]), "{}", text);
         }
-        
-        false
+        assert_eq!(clauses("After we ate, we went home."), (2, vec!["adverbial: After we ate".to_string()]));
+        assert_eq!(clauses("He has been sad since his dog died."), (2, vec!["adverbial: since his dog died".to_string()]));
+        assert_eq!(clauses("The house where I grew up is old."), (2, vec!["relative: where I grew up".to_string()]));
+        assert_eq!(clauses("The reason why he left is unclear."), (2, vec!["relative: why he left".to_string()]));
+        assert_eq!(clauses("How she did it is a mystery."), (2, vec!["complement: How she did it".to_string()]));
+        assert_eq!(clauses("Where he goes, trouble follows."), (2, vec!["adverbial: Where he goes".to_string()]));
+        assert_eq!(clauses("Whether you like it or not, we are leaving."), (2, vec!["adverbial: Whether you like it or not".to_string()]));
     }
-    
-    /// Check if context suggests enumeration (comma followed by and/or/adjective)
-    fn is_enumeration_context(word: &str, tag: &str) -> bool {
-        let lower = word.to_lowercase();
-        // Enumeration: followed by coordinating conjunction or another adjective
-        lower == "and" || lower == "or" || tag == "JJ" || tag == "NN"
+
+    #[test]
+    fn clause_density_counts_clauses_not_words() {
+        let density = |text: &str| SyntacticAnalyzer::analyze(text, &[tagged(text)], to_offsets).clause_density;
+        assert_eq!(density("I like bread and butter."), 0.0);
+        assert_eq!(density("It was so cold that we stayed inside."), 1.0);
+        assert_eq!(density("It rained, so we stayed."), 1.0);
+        assert_eq!(density("He ran and she walked because it rained."), 2.0);
     }
-    
-    /// Detect potential main clause verb (helps close implicit clauses)
-    fn is_main_clause_verb_signal(word: &str, tag: &str) -> bool {
-        // Modal verbs or finite verbs that likely signal main clause
-        let lower = word.to_lowercase();
-        tag == "MD" || // Modal
-        (tag.starts_with("VB") && matches!(lower.as_str(), "is" | "are" | "was" | "were" | "has" | "have" | "had"))
+
+    #[test]
+    fn metrics_report_depths_per_sentence() {
+        let text = "I know that she thinks that he left. It rained. We stayed because it rained.";
+        let sentences: Vec<Vec<TaggedToken>> = split_sentences(text)
+            .into_iter()
+            .map(|range| {
+                let mut sent = tagged(&text[range.clone()]);
+                for token in &mut sent {
+                    token.span = token.span.start + range.start..token.span.end + range.start;
+                }
+                sent
+            })
+            .collect();
+        let metrics = SyntacticAnalyzer::analyze(text, &sentences, to_offsets);
+        assert_eq!(metrics.sentence_depths.iter().map(|d| d.max).collect::<Vec<_>>(), [3, 1, 2]);
+        assert_eq!(metrics.depth_histogram, BTreeMap::from([(1, 1), (2, 1), (3, 1)]));
+        assert_eq!(metrics.max_tree_depth, 3);
+        assert!((metrics.average_tree_depth - 2.0).abs() < 1e-9);
+        assert_eq!(metrics.sentence_depths[1].mean, 1.0);
+        assert_eq!(metrics.clause_types.get("complement"), Some(&2));
+        let first = &metrics.subordinate_clauses[0];
+        assert_eq!((first.sentence, first.opener.as_deref(), first.offsets.start, first.offsets.end), (0, Some("that"), 7, 35));
     }
 }
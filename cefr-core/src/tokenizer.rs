@@ -0,0 +1,371 @@
+use std::ops::Range;
+
+/// What a token is. Only words are looked up and scored; numbers, URLs and
+/// the like are counted and reported but carry no vocabulary level.
+/// Punctuation is kept so syntax and discourse rules can see clause
+/// boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    Punct,
+    /// `'s` / `'d` split off its host; resolved after tagging (see clitics.rs)
+    Clitic,
+    Number,   // 42, 3,000, 3.50, 10:30
+    Ordinal,  // 3rd, 21st
+    Year,     // 1990, 1990s, 1990's, 90s
+    Currency, // $3.50, £20
+    Url,
+    Email,
+}
+
+impl TokenKind {
+    /// Counted as a word but never looked up or scored.
+    pub fn is_non_lexical(self) -> bool {
+        matches!(self, TokenKind::Number | TokenKind::Ordinal | TokenKind::Year
+            | TokenKind::Currency | TokenKind::Url | TokenKind::Email)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenKind::Word => "word",
+            TokenKind::Punct => "punct",
+            TokenKind::Clitic => "clitic",
+            TokenKind::Number => "number",
+            TokenKind::Ordinal => "ordinal",
+            TokenKind::Year => "year",
+            TokenKind::Currency => "currency",
+            TokenKind::Url => "url",
+            TokenKind::Email => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub text: String,
+    pub kind: TokenKind,
+    /// Byte range in the tokenized text; expansions ("do" + "not") split the
+    /// written form ("do" + "n't")
+    pub span: Range<usize>,
+}
+
+impl Token {
+    fn word(text: impl Into<String>, span: Range<usize>) -> Self {
+        Token { text: text.into(), kind: TokenKind::Word, span }
+    }
+
+    fn punct(text: impl Into<String>, span: Range<usize>) -> Self {
+        Token { text: text.into(), kind: TokenKind::Punct, span }
+    }
+
+    fn clitic(text: impl Into<String>, span: Range<usize>) -> Self {
+        Token { text: text.into(), kind: TokenKind::Clitic, span }
+    }
+}
+
+/// Characters that may trail a URL or email but belong to the sentence.
+fn is_trailing_punct(c: char) -> bool {
+    matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']' | '}' | '"' | '\'')
+}
+
+/// Length in chars of a URL or email starting at `chars[0]`, if there is one.
+fn link_len(chars: &[char]) -> Option<(usize, TokenKind)> {
+    let mut len = chars.iter().position(|c| c.is_whitespace()).unwrap_or(chars.len());
+    while len > 0 && is_trailing_punct(chars[len - 1]) {
+        len -= 1;
+    }
+    let chunk: String = chars[..len].iter().collect::<String>().to_ascii_lowercase();
+
+    if ["http://", "https://", "www."].iter().any(|p| chunk.starts_with(p) && chunk.len() > p.len()) {
+        return Some((len, TokenKind::Url));
+    }
+
+    // local@domain.tld
+    let (local, domain) = chunk.split_once('@')?;
+    let (host, tld) = domain.rsplit_once('.')?;
+    let valid = !local.is_empty()
+        && local.chars().all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c))
+        && !host.is_empty()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic());
+    valid.then_some((len, TokenKind::Email))
+}
+
+/// Numerals, ordinals and years among word-like runs.
+fn classify(word: &str) -> TokenKind {
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return TokenKind::Word;
+    }
+    let rest = &word[digits..];
+    let lower = rest.to_ascii_lowercase();
+
+    if rest.is_empty() {
+        let is_year = digits == 4 && matches!(word.parse::<u32>(), Ok(1100..=2099));
+        return if is_year { TokenKind::Year } else { TokenKind::Number };
+    }
+    // Decades, also with an apostrophe, which is no clitic here
+    if matches!(lower.as_str(), "s" | "'s") && (digits == 2 || digits == 4) {
+        return TokenKind::Year; // 1990s, 1990's, 90s
+    }
+    if matches!(lower.as_str(), "st" | "nd" | "rd" | "th") {
+        return TokenKind::Ordinal;
+    }
+    // Separators between digits: 3,000 / 3.50 / 10:30 / 1990-2000
+    let separated = rest.chars().all(|c| c.is_ascii_digit() || ",.:-".contains(c));
+    if separated && rest.ends_with(|c: char| c.is_ascii_digit()) {
+        return TokenKind::Number;
+    }
+    TokenKind::Word
+}
+
+
+/// Split a sentence into word and punctuation tokens.
+///
+/// Words keep internal apostrophes, hyphens and periods ("don't", "well-known",
+/// "U.S") and digit group separators ("3,000", "10:30"); every other
+/// non-space character is punctuation. Runs of the same punctuation mark stay
+/// together ("...", "--", "!!"). Unambiguous contractions are expanded;
+/// `'s` and `'d` become clitic tokens ("John's" -> "John" + "'s"). URLs,
+/// emails, numbers, ordinals, years and currency amounts get their own kinds.
+pub fn tokenize_sentence(text: &str) -> Vec<Token> {
+    let (offsets, chars): (Vec<usize>, Vec<char>) = text.char_indices().unzip();
+    let byte_at = |i: usize| offsets.get(i).copied().unwrap_or(text.len());
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if let Some((len, kind)) = c.is_alphanumeric().then(|| link_len(&chars[i..])).flatten() {
+            let span = byte_at(i)..byte_at(i + len);
+            tokens.push(Token { text: text[span.clone()].to_string(), kind, span });
+            i += len;
+        } else if c.is_alphanumeric() {
+            let start = i;
+            i = word_end(&chars, i);
+            let span = byte_at(start)..byte_at(i);
+            let word = &text[span.clone()];
+            let kind = classify(word);
+            if kind != TokenKind::Word {
+                tokens.push(Token { text: word.to_string(), kind, span });
+                continue;
+            }
+            let parts = expand_contraction(word);
+            let split = span.start + contraction_split(word);
+            let count = parts.len();
+            for (n, part) in parts.into_iter().enumerate() {
+                let part_span = match (count, n) {
+                    (1, _) => span.clone(),
+                    (_, 0) => span.start..split,
+                    _ => split..span.end,
+                };
+                match split_clitic(&part) {
+                    Some((host, clitic)) => {
+                        let mid = part_span.end - clitic.len();
+                        tokens.push(Token::word(host, part_span.start..mid));
+                        tokens.push(Token::clitic(clitic, mid..part_span.end));
+                    }
+                    None => tokens.push(Token::word(part, part_span)),
+                }
+            }
+        } else if is_currency_symbol(c) && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+            let start = i;
+            i = word_end(&chars, i + 1);
+            let span = byte_at(start)..byte_at(i);
+            tokens.push(Token { text: text[span.clone()].to_string(), kind: TokenKind::Currency, span });
+        } else {
+            let start = i;
+            while i < chars.len() && chars[i] == c {
+                i += 1;
+            }
+            let span = byte_at(start)..byte_at(i);
+            tokens.push(Token::punct(&text[span.clone()], span));
+        }
+    }
+    tokens
+}
+
+/// End (exclusive) of the word-like run starting at `chars[start]`.
+fn word_end(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() && (chars[i].is_alphanumeric() || joins_word(chars, i)) {
+        i += 1;
+    }
+    i
+}
+
+fn is_currency_symbol(c: char) -> bool {
+    matches!(c, '$' | '\u{00A3}' | '\u{20AC}' | '\u{00A5}')
+}
+
+/// Whether the non-alphanumeric `chars[i]` continues the word it follows.
+fn joins_word(chars: &[char], i: usize) -> bool {
+    let (Some(prev), Some(next)) = (i.checked_sub(1).map(|p| chars[p]), chars.get(i + 1)) else {
+        return false;
+    };
+    match chars[i] {
+        '\'' | '\u{2019}' | '-' | '.' | '&' => prev.is_alphanumeric() && next.is_alphanumeric(),
+        ',' | ':' => prev.is_ascii_digit() && next.is_ascii_digit(),
+        _ => false,
+    }
+}
+
+/// Where a contraction's written form divides between its two expanded
+/// words: "do|n't", "wo|n't", "can|not", "they|'re".
+fn contraction_split(word: &str) -> usize {
+    let lower = word.to_ascii_lowercase();
+    if lower.ends_with("n't") {
+        word.len() - 3
+    } else if lower == "cannot" {
+        3
+    } else {
+        word.rfind('\'').unwrap_or(word.len())
+    }
+}
+
+/// "John's" -> ("John", "'s"), "she'd" -> ("she", "'d").
+fn split_clitic(word: &str) -> Option<(&str, &str)> {
+    let lower = word.to_ascii_lowercase();
+    if word.len() > 2 && (lower.ends_with("'s") || lower.ends_with("'d")) {
+        let (host, clitic) = word.split_at(word.len() - 2);
+        Some((host, clitic))
+    } else {
+        None
+    }
+}
+
+fn expand_contraction(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    match lower.as_str() {
+        "don't" => vec!["do".to_string(), "not".to_string()],
+        "doesn't" => vec!["does".to_string(), "not".to_string()],
+        "didn't" => vec!["did".to_string(), "not".to_string()],
+        "can't" => vec!["can".to_string(), "not".to_string()],
+        "cannot" => vec!["can".to_string(), "not".to_string()],
+        "won't" => vec!["will".to_string(), "not".to_string()],
+        "shan't" => vec!["shall".to_string(), "not".to_string()],
+        "isn't" => vec!["is".to_string(), "not".to_string()],
+        "aren't" => vec!["are".to_string(), "not".to_string()],
+        "wasn't" => vec!["was".to_string(), "not".to_string()],
+        "weren't" => vec!["were".to_string(), "not".to_string()],
+        "couldn't" => vec!["could".to_string(), "not".to_string()],
+        "shouldn't" => vec!["should".to_string(), "not".to_string()],
+        "wouldn't" => vec!["would".to_string(), "not".to_string()],
+        "haven't" => vec!["have".to_string(), "not".to_string()],
+        "hasn't" => vec!["has".to_string(), "not".to_string()],
+        "hadn't" => vec!["had".to_string(), "not".to_string()],
+        
+        // Pronoun + be/have/will
+        // 'm -> am
+        // 're -> are
+        // 've -> have
+        // 'll -> will
+        s if s.ends_with("'m") && s.len() > 2 => {
+            vec![word[..word.len()-2].to_string(), "am".to_string()]
+        },
+        s if s.ends_with("'re") && s.len() > 3 => {
+            vec![word[..word.len()-3].to_string(), "are".to_string()]
+        },
+        s if s.ends_with("'ve") && s.len() > 3 => {
+            vec![word[..word.len()-3].to_string(), "have".to_string()]
+        },
+        s if s.ends_with("'ll") && s.len() > 3 => {
+            vec![word[..word.len()-3].to_string(), "will".to_string()]
+        },
+        // 's (is/has/possessive) and 'd (had/would) are ambiguous and left to
+        // the clitic analyzer, except in "let's"
+        "let's" => vec!["let".to_string(), "us".to_string()],
+        
+        _ => vec![word.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &str) -> Vec<(String, TokenKind)> {
+        tokenize_sentence(text).into_iter().map(|t| (t.text, t.kind)).collect()
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        tokenize_sentence(text).into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn punctuation_becomes_separate_non_word_tokens() {
+        let tokens = texts("Well, it rained; we stayed (mostly) inside!");
+        let punct: Vec<&str> = tokens.iter().filter(|(_, k)| *k == TokenKind::Punct).map(|(t, _)| t.as_str()).collect();
+        assert_eq!(punct, vec![",", ";", "(", ")", "!"]);
+        assert_eq!(tokens[0], ("Well".to_string(), TokenKind::Word));
+    }
+
+    #[test]
+    fn repeated_marks_stay_together_and_internal_marks_stay_in_words() {
+        assert_eq!(words("Wait... what?!"), vec!["Wait", "...", "what", "?", "!"]);
+        assert_eq!(words("a well-known U.S. firm"), vec!["a", "well-known", "U.S", ".", "firm"]);
+    }
+
+    #[test]
+    fn spans_point_into_the_text() {
+        let text = "Hi, you.";
+        for token in tokenize_sentence(text) {
+            assert_eq!(&text[token.span.clone()], token.text);
+        }
+    }
+
+    #[test]
+    fn contractions_expand_and_split_their_spans() {
+        let tokens = tokenize_sentence("I don't know");
+        let parts: Vec<(&str, &str)> = tokens.iter().map(|t| (t.text.as_str(), &"I don't know"[t.span.clone()])).collect();
+        assert_eq!(parts, vec![("I", "I"), ("do", "do"), ("not", "n't"), ("know", "know")]);
+    }
+
+    #[test]
+    fn numerals_years_ordinals_and_currency_get_their_own_kinds() {
+        let kinds = |text: &str| texts(text).into_iter().filter(|(_, k)| *k != TokenKind::Punct).map(|(t, k)| (t, k.name())).collect::<Vec<_>>();
+        assert_eq!(kinds("In 1990 the 1990s 3rd $3.50 3,000 10:30 42"), vec![
+            ("In".to_string(), "word"),
+            ("1990".to_string(), "year"),
+            ("the".to_string(), "word"),
+            ("1990s".to_string(), "year"),
+            ("3rd".to_string(), "ordinal"),
+            ("$3.50".to_string(), "currency"),
+            ("3,000".to_string(), "number"),
+            ("10:30".to_string(), "number"),
+            ("42".to_string(), "number"),
+        ]);
+        assert!(TokenKind::Year.is_non_lexical() && !TokenKind::Word.is_non_lexical());
+    }
+
+    #[test]
+    fn decades_with_an_apostrophe_stay_years() {
+        assert_eq!(texts("in 1990's London")[1], ("1990's".to_string(), TokenKind::Year));
+        assert_eq!(texts("the 80's")[1], ("80's".to_string(), TokenKind::Year));
+        assert_eq!(texts("John's")[1], ("'s".to_string(), TokenKind::Clitic));
+    }
+
+    #[test]
+    fn urls_and_emails_are_single_tokens_without_trailing_punctuation() {
+        assert_eq!(texts("See https://example.com/a?b=1."), vec![
+            ("See".to_string(), TokenKind::Word),
+            ("https://example.com/a?b=1".to_string(), TokenKind::Url),
+            (".".to_string(), TokenKind::Punct),
+        ]);
+        assert_eq!(texts("Mail jo.doe@mail.example.org, please")[1], ("jo.doe@mail.example.org".to_string(), TokenKind::Email));
+        assert_eq!(texts("at www.example.com")[1].1, TokenKind::Url);
+        assert_eq!(texts("not@email")[0].1, TokenKind::Word);
+    }
+
+    #[test]
+    fn hyphenated_compounds_stay_whole() {
+        assert_eq!(texts("a well-known e-mail"), vec![
+            ("a".to_string(), TokenKind::Word),
+            ("well-known".to_string(), TokenKind::Word),
+            ("e-mail".to_string(), TokenKind::Word),
+        ]);
+    }
+}
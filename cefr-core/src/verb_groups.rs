@@ -0,0 +1,397 @@
+//! Finite verb groups: an auxiliary chain and its main verb ("has not yet
+//! been finished", "will be leaving", "did you see"), labeled with tense,
+//! aspect, voice and modality.
+//!
+//! A group opens on a modal or a verb tagged for tense (VBD/VBZ/VBP) and
+//! extends while the last verb is an auxiliary and the next verb follows,
+//! past adverbs, "not" and, in questions, the subject. A verb coordinated
+//! with the main verb before it shares that group's auxiliaries ("have lived
+//! here and worked there").
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use serde::Serialize;
+use crate::lemmatizer::lemmatize;
+use crate::pos::TaggedToken;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Tense {
+    Present,
+    Past,
+    Future,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Aspect {
+    Simple,
+    Progressive,
+    Perfect,
+    PerfectProgressive,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Voice {
+    Active,
+    Passive,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerbGroup {
+    /// Verbs of the group in order, auxiliaries first; indices into the sentence
+    pub verbs: Vec<usize>,
+    /// First to last verb, including adverbs and a question subject in between
+    pub span: Range<usize>,
+    pub tense: Tense,
+    pub aspect: Aspect,
+    pub voice: Voice,
+    /// The modal, lowercased ("will", "could"); "going to" futures have none
+    pub modal: Option<String>,
+    pub negated: bool,
+    /// Lemma of the main verb
+    pub lemma: String,
+}
+
+impl fmt::Display for Tense {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tense::Present => "present",
+            Tense::Past => "past",
+            Tense::Future => "future",
+        })
+    }
+}
+
+impl fmt::Display for Aspect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Aspect::Simple => "simple",
+            Aspect::Progressive => "progressive",
+            Aspect::Perfect => "perfect",
+            Aspect::PerfectProgressive => "perfect progressive",
+        })
+    }
+}
+
+impl VerbGroup {
+    /// Tense and aspect as grammars name them: "past perfect progressive"
+    pub fn tense_name(&self) -> String {
+        format!("{} {}", self.tense, self.aspect)
+    }
+
+    /// One-line description, e.g. "present perfect, passive, negated".
+    /// Modals other than will/shall are named: "present simple, modal 'might'".
+    pub fn description(&self) -> String {
+        let mut parts = vec![self.tense_name()];
+        if self.voice == Voice::Passive {
+            parts.push("passive".to_string());
+        }
+        if let Some(modal) = self.modal.as_deref().filter(|m| !matches!(*m, "will" | "shall")) {
+            parts.push(format!("modal '{}'", modal));
+        }
+        if self.negated {
+            parts.push("negated".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// How often each tense and aspect, voice and modality occur.
+#[derive(Serialize, Default, Debug)]
+pub struct TenseDistribution {
+    /// Finite verb groups
+    pub total: usize,
+    /// `tense_name()` -> count, e.g. "present simple": 12
+    pub tenses: BTreeMap<String, usize>,
+    pub passive: usize,
+    pub modal: usize,
+    pub negated: usize,
+}
+
+impl TenseDistribution {
+    pub fn add(&mut self, group: &VerbGroup) {
+        self.total += 1;
+        *self.tenses.entry(group.tense_name()).or_default() += 1;
+        self.passive += (group.voice == Voice::Passive) as usize;
+        self.modal += group.modal.as_deref().is_some_and(|m| !matches!(m, "will" | "shall")) as usize;
+        self.negated += group.negated as usize;
+    }
+}
+
+pub(crate) fn lower(token: &TaggedToken) -> String {
+    token.word.to_lowercase()
+}
+
+pub(crate) fn is_be(word: &str) -> bool {
+    matches!(word, "am" | "is" | "are" | "was" | "were" | "be" | "been" | "being")
+}
+
+pub(crate) fn is_auxiliary(word: &str, tag: &str) -> bool {
+    tag == "MD" || is_be(word) || matches!(word, "do" | "does" | "did" | "have" | "has" | "had" | "having")
+}
+
+fn opens_group(tag: &str) -> bool {
+    matches!(tag, "MD" | "VBD" | "VBZ" | "VBP")
+}
+
+/// Adverbs and negation may sit inside a group ("has not always been")
+fn is_inner_adverb(token: &TaggedToken) -> bool {
+    token.tag.starts_with("RB") && token.is_word()
+}
+
+/// Past a question's subject at `k`: a pronoun or a short noun phrase
+/// ("the children", "your brother's friend"). Returns `k` itself when no
+/// verb follows the subject, so "Is the car red?" stays a one-verb group.
+fn skip_subject(sent: &[TaggedToken], k: usize) -> usize {
+    let end = match sent.get(k).map(|t| t.tag.as_str()) {
+        Some("PRP" | "EX") => k + 1,
+        _ => {
+            let np = sent[k..]
+                .iter()
+                .take(5)
+                .take_while(|t| t.is_word() && matches!(t.tag.as_str(), "DT" | "PDT" | "PRP$" | "CD" | "POS" | "JJ" | "JJR" | "JJS" | "NN" | "NNS" | "NNP" | "NNPS"))
+                .count();
+            if np == 0 || !sent[k + np - 1].tag.starts_with("NN") {
+                return k;
+            }
+            k + np
+        }
+    };
+    let verb = (end..sent.len()).find(|&v| !is_inner_adverb(&sent[v]));
+    if verb.is_some_and(|v| sent[v].is_word() && sent[v].tag.starts_with("VB")) { end } else { k }
+}
+
+/// All finite verb groups of a tagged sentence, left to right.
+pub fn chunk_verb_groups(sent: &[TaggedToken]) -> Vec<VerbGroup> {
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < sent.len() {
+        if let Some(group) = groups.last().and_then(|prev| coordinated_with(sent, i, prev)) {
+            i += 1;
+            groups.push(group);
+            continue;
+        }
+        if !sent[i].is_word() || !opens_group(&sent[i].tag) {
+            i += 1;
+            continue;
+        }
+        let group = chunk_from(sent, i);
+        i = group.span.end;
+        groups.push(group);
+    }
+    groups
+}
+
+/// "have lived here and (worked) there", "was arrested and (charged)": a verb
+/// right after "and"/"or" in the form the previous group's main verb has
+/// takes that group's auxiliaries, and so its tense, aspect and voice.
+fn coordinated_with(sent: &[TaggedToken], i: usize, prev: &VerbGroup) -> Option<VerbGroup> {
+    let (&main, auxiliaries) = prev.verbs.split_last()?;
+    let after_coordinator = i > 0 && matches!(lower(&sent[i - 1]).as_str(), "and" | "or");
+    let same_form = match (sent[main].tag.as_str(), sent[i].tag.as_str()) {
+        (a, b) if a == b => true,
+        // The second verb lacks the context that told the first one apart
+        ("VBN", "VBD") | ("VB", "VBP") => true,
+        _ => false,
+    };
+    if auxiliaries.is_empty() || !after_coordinator || !same_form || !sent[i].is_word() {
+        return None;
+    }
+    let mut verbs = auxiliaries.to_vec();
+    verbs.push(i);
+    let mut tags: Vec<&str> = auxiliaries.iter().map(|&v| sent[v].tag.as_str()).collect();
+    tags.push(&sent[main].tag);
+    let mut group = label(sent, verbs, &tags, i..i + 1, false, false);
+    group.verbs = vec![i];
+    Some(group)
+}
+
+fn chunk_from(sent: &[TaggedToken], start: usize) -> VerbGroup {
+    let question = start == 0
+        || sent[..start].iter().rev().find(|t| t.is_word()).is_none_or(|t| matches!(t.tag.as_str(), "WRB" | "WP" | "WDT" | "CC"));
+    let mut verbs = vec![start];
+    let mut negated = false;
+    let mut end = start + 1;
+    let mut k = start + 1;
+    loop {
+        let last = *verbs.last().unwrap();
+        if !is_auxiliary(&lower(&sent[last]), &sent[last].tag) {
+            break;
+        }
+        while k < sent.len() && is_inner_adverb(&sent[k]) {
+            negated |= matches!(lower(&sent[k]).as_str(), "not" | "never");
+            k += 1;
+        }
+        // "Did you see", "Are the children sleeping": the subject follows the
+        // first auxiliary
+        if verbs.len() == 1 && question {
+            k = skip_subject(sent, k);
+            while k < sent.len() && is_inner_adverb(&sent[k]) {
+                negated |= matches!(lower(&sent[k]).as_str(), "not" | "never");
+                k += 1;
+            }
+        }
+        // After an auxiliary any verb tag continues the group: taggers read
+        // "have come" or "did go" as VB/VBP as often as VBN/VB
+        if k < sent.len() && sent[k].is_word() && sent[k].tag.starts_with("VB") {
+            verbs.push(k);
+            k += 1;
+            end = k;
+        } else {
+            break;
+        }
+    }
+
+    // "is going to leave": a future with "going to"
+    let mut going_to = false;
+    if let [.., aux, going] = verbs[..] {
+        if is_be(&lower(&sent[aux])) && lower(&sent[going]) == "going" && sent[going].tag == "VBG" {
+            let to = end;
+            let verb = to + 1;
+            if verb < sent.len() && lower(&sent[to]) == "to" && sent[verb].tag == "VB" {
+                verbs.pop();
+                verbs.push(verb);
+                end = verb + 1;
+                going_to = true;
+            }
+        }
+    }
+
+    let tags: Vec<&str> = verbs.iter().map(|&v| sent[v].tag.as_str()).collect();
+    label(sent, verbs.clone(), &tags, start..end, negated, going_to)
+}
+
+/// Label the group of `verbs`, read with `tags`.
+fn label(sent: &[TaggedToken], verbs: Vec<usize>, tags: &[&str], span: Range<usize>, negated: bool, going_to: bool) -> VerbGroup {
+    let words: Vec<String> = verbs.iter().map(|&v| lower(&sent[v])).collect();
+    let modal = (tags[0] == "MD").then(|| words[0].clone());
+
+    // Modals mark modality, not time: "might rain tomorrow" is present,
+    // and `modal` names the verb
+    let tense = match modal.as_deref() {
+        Some("will" | "shall") => Tense::Future,
+        Some(_) => Tense::Present,
+        None if going_to => Tense::Future,
+        None if tags[0] == "VBD" => Tense::Past,
+        None => Tense::Present,
+    };
+
+    // Each auxiliary marks what the next verb is: have + participle (perfect),
+    // be + -ing (progressive), be + participle (passive)
+    let (mut perfect, mut progressive, mut passive) = (false, false, false);
+    for w in 1..verbs.len() {
+        let (aux, next, next_tag) = (words[w - 1].as_str(), words[w].as_str(), tags[w]);
+        if matches!(aux, "have" | "has" | "had" | "having") && next_tag != "VBG" {
+            perfect = true;
+        } else if is_be(aux) && next_tag == "VBG" {
+            progressive = true;
+        } else if is_be(aux) && next_tag == "VBN" && next != "been" {
+            passive = true;
+        }
+    }
+    let aspect = match (perfect, progressive) {
+        (true, true) => Aspect::PerfectProgressive,
+        (true, false) => Aspect::Perfect,
+        (false, true) => Aspect::Progressive,
+        (false, false) => Aspect::Simple,
+    };
+
+    let main = &sent[*verbs.last().unwrap()];
+    VerbGroup {
+        lemma: lemmatize(&main.word, Some(&main.tag)).to_lowercase(),
+        verbs,
+        span,
+        tense,
+        aspect,
+        voice: if passive { Voice::Passive } else { Voice::Active },
+        modal,
+        negated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pos::{tag_sentence, TaggerMode};
+    use crate::tokenizer::tokenize_sentence;
+
+    /// Each group's words and description.
+    fn groups(text: &str) -> Vec<(String, String)> {
+        let sent = tag_sentence(&tokenize_sentence(text), TaggerMode::default());
+        chunk_verb_groups(&sent)
+            .iter()
+            .map(|g| {
+                let words: Vec<&str> = sent[g.span.clone()].iter().map(|t| t.word.as_str()).collect();
+                (words.join(" "), g.description())
+            })
+            .collect()
+    }
+
+    fn group(text: &str) -> (String, String) {
+        let mut found = groups(text);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        found.remove(0)
+    }
+
+    #[test]
+    fn auxiliary_chains_with_adverbs_and_negation() {
+        assert_eq!(group("The report has not yet been finished."), ("has not yet been finished".into(), "present perfect, passive, negated".into()));
+        assert_eq!(group("She will be leaving soon.").1, "future progressive");
+        assert_eq!(group("He had been waiting for hours.").1, "past perfect progressive");
+        assert_eq!(group("It can't be done.").1, "present simple, passive, modal 'can', negated");
+        assert_eq!(group("I am going to call her."), ("am going to call".into(), "future simple".into()));
+    }
+
+    #[test]
+    fn clitics_and_participles_keep_their_reading() {
+        assert_eq!(group("She's gone home."), ("has gone".into(), "present perfect".into()));
+        assert_eq!(group("I'd seen it.").1, "past perfect");
+        assert_eq!(group("He was born in 1990.").1, "past simple, passive");
+        assert_eq!(group("They didn't like it."), ("did not like".into(), "past simple, negated".into()));
+        assert_eq!(group("Don't go!"), ("do not go".into(), "present simple, negated".into()));
+    }
+
+    #[test]
+    fn questions_carry_their_subject_inside_the_group() {
+        assert_eq!(group("Did you see it?").0, "Did you see");
+        assert_eq!(group("Are the children sleeping?"), ("Are the children sleeping".into(), "present progressive".into()));
+        assert_eq!(group("Where does your brother live?").0, "does your brother live");
+        assert_eq!(group("Is the car red?").0, "Is");
+    }
+
+    #[test]
+    fn the_distribution_counts_every_group() {
+        let sent = tag_sentence(&tokenize_sentence("We have lived here and worked there, but it could be sold."), TaggerMode::default());
+        let mut distribution = TenseDistribution::default();
+        for g in chunk_verb_groups(&sent) {
+            distribution.add(&g);
+        }
+        assert_eq!(distribution.total, 3);
+        assert_eq!(distribution.tenses["present perfect"], 2);
+        assert_eq!(distribution.tenses["present simple"], 1);
+        assert!(!distribution.tenses.contains_key("past simple"));
+        assert_eq!((distribution.passive, distribution.modal, distribution.negated), (1, 1, 0));
+    }
+
+    #[test]
+    fn modals_are_present_and_named() {
+        assert_eq!(group("It might rain tomorrow.").1, "present simple, modal 'might'");
+        assert_eq!(group("It could be sold.").1, "present simple, passive, modal 'could'");
+        assert_eq!(group("She would have helped.").1, "present perfect, modal 'would'");
+    }
+
+    #[test]
+    fn coordinated_verbs_share_the_auxiliaries_before_them() {
+        assert_eq!(groups("He was arrested and charged."), [
+            ("was arrested".to_string(), "past simple, passive".to_string()),
+            ("charged".to_string(), "past simple, passive".to_string()),
+        ]);
+        assert_eq!(groups("She is singing and dancing.")[1], ("dancing".to_string(), "present progressive".to_string()));
+        assert_eq!(groups("She had cooked and cleaned.")[1], ("cleaned".to_string(), "past perfect".to_string()));
+        // Without auxiliaries there is nothing to share
+        assert_eq!(groups("She sang and danced.")[1], ("danced".to_string(), "past simple".to_string()));
+    }
+}
@@ -36,11 +36,21 @@ export interface CefrAnalysisResult {
         adjusted_score?: number;
         syntax: any;
         discourse: any;
+        grammar?: any;
+        tenses?: any;
         sentence_count: number;
         avg_sentence_length: number;
     };
 }
 
+// 原文位置: UTF-8 字节 (start/end) 与 UTF-16 码元 (start_utf16/end_utf16, 可直接用于 JS 字符串)
+interface WasmOffsets {
+    start: number;
+    end: number;
+    start_utf16: number;
+    end_utf16: number;
+}
+
 // 来自 WASM 的原始结果
 interface WasmAnalysisResult {
     cefr_level: string;
@@ -53,13 +63,64 @@ interface WasmAnalysisResult {
         avg_sentence_length: number;
         syntax: any;
         discourse: any;
+        grammar: {
+            level: string | null; // level of the hardest structures found
+            score: number;
+            structures: Array<{
+                id: string; // "third_conditional", "negative_inversion", ...
+                name: string;
+                level: string;
+                count: number;
+                examples: Array<{ sentence: number; offsets: WasmOffsets }>; // sentence: index into `sentences`
+            }>;
+        };
+        tenses: {
+            total: number; // finite verb groups
+            tenses: { [tenseAndAspect: string]: number }; // "present simple", "past perfect progressive", ...
+            passive: number;
+            modal: number;
+            negated: number;
+        };
     };
     details: Array<{
         text: string;
         lemma: string;
         pos: string;
-        level: string; // "A1", "B2", "Unknown", etc.
+        level: string; // "A1", "B2", "Unknown", "Entity", "NonLexical", etc.
+        kind: string; // "word", "number", "ordinal", "year", "currency", "url", "email"
         is_phrase: boolean;
+        phrase: number | null; // index into `phrases`
+        source: string | null;
+        sense: string | null; // gloss of the chosen sense for polysemous words
+        clitic: string | null; // 's/'d reading ("is", "has", "had", "would") or "possessive"
+        offsets: WasmOffsets;
+    }>;
+    phrases: Array<{
+        kind: 'phrase' | 'phrasal_verb';
+        phrase_id: number | null; // null for phrasal verbs
+
+        text: string;
+        level: string;
+        tokens: number[]; // indices into `details`
+        offsets: WasmOffsets;
+    }>;
+    verb_groups: Array<{
+        sentence: number; // index into `sentences`
+        text: string;
+        lemma: string; // main verb
+        tense: 'present' | 'past' | 'future';
+        aspect: 'simple' | 'progressive' | 'perfect' | 'perfect_progressive';
+        voice: 'active' | 'passive';
+        modal: string | null;
+        negated: boolean;
+        description: string; // e.g. "past perfect, passive, negated"
+        tokens: number[]; // the verbs; indices into `details`
+        offsets: WasmOffsets;
+    }>;
+    sentences: Array<{
+        offsets: WasmOffsets;
+        token_start: number; // details[token_start..token_end]
+        token_end: number;
     }>;
 }
 
@@ -166,6 +227,18 @@ export async function loadDictionaryIndex(): Promise<void> {
         // buffer 是 Buffer，是 Uint8Array 的子类
         wasm.load_fst_index(buffer);
         console.log('[FST] 索引加载成功');
+
+        // 词形索引 (可选, 由 build_dict 生成): 供 lemma_of / inflections_of 使用
+        const exchangeFstPath = path.join(resourcesPath, 'exchange.fst');
+        const exchangeDataPath = path.join(resourcesPath, 'exchange.data');
+        if (await fs.stat(exchangeFstPath).then(() => true).catch(() => false)) {
+            const [exchangeFst, exchangeData] = await Promise.all([
+                fs.readFile(exchangeFstPath),
+                fs.readFile(exchangeDataPath),
+            ]);
+            wasm.load_exchange_index(exchangeFst, exchangeData);
+            console.log('[FST] 词形索引加载成功');
+        }
         (global as any).__fstLoaded = true;
     } catch (e) {
         console.error('[FST] 加载索引失败:', e);
@@ -206,6 +279,10 @@ function mapWasmToResult(wasmResult: WasmAnalysisResult): CefrAnalysisResult {
     details.forEach(token => {
         // Clean level string (unquote if needed, though serde handles it)
         let level = token.level.replace(/"/g, '');
+        // Numbers, URLs etc. are not vocabulary: neither known nor unknown
+        if (level === 'NonLexical') {
+            return;
+        }
         // Validate level key to avoid crashing on unexpected values
         if (!counts.hasOwnProperty(level)) {
             level = 'Unknown';
@@ -216,7 +293,8 @@ function mapWasmToResult(wasmResult: WasmAnalysisResult): CefrAnalysisResult {
     });
 
     unknownWordsCount = counts['Unknown'];
-    const knownWordsCount = totalWords - unknownWordsCount;
+    const nonLexicalCount = details.filter(d => d.level === 'NonLexical').length;
+    const knownWordsCount = totalWords - unknownWordsCount - nonLexicalCount;
 
     // Safe division
     const distribution: any = {};
@@ -256,6 +334,8 @@ function mapWasmToResult(wasmResult: WasmAnalysisResult): CefrAnalysisResult {
             adjusted_score: wasmResult.adjusted_score,
             syntax: wasmResult.metrics.syntax,
             discourse: wasmResult.metrics.discourse,
+            grammar: wasmResult.metrics.grammar,
+            tenses: wasmResult.metrics.tenses,
             sentence_count: wasmResult.metrics.sentence_count,
             avg_sentence_length: wasmResult.metrics.avg_sentence_length
         }
@@ -313,6 +393,27 @@ export function setupCefrAnalyzerHandlers(): void {
         }
     });
 
+    /**
+     * 句子浅层句法分析：NP/VP/PP 短语块及主句、从句
+     */
+    ipcMain.handle('cefr:parse', async (event, { text }: { text: string }) => {
+        if (!text || typeof text !== 'string') {
+            return { success: false, error: '无效的输入：文本为空' };
+        }
+
+        try {
+            const wasmModule = await loadWasmModule();
+            // parse_sentence(text: &str) -> JsValue ({ tokens, clauses })
+            return { success: true, data: wasmModule.parse_sentence(text) };
+        } catch (error) {
+            console.error('[CEFR] Parse failed:', error);
+            return {
+                success: false,
+                error: error instanceof Error ? error.message : String(error)
+            };
+        }
+    });
+
     /**
      * 检查分析器是否可用
      */
@@ -28,6 +28,7 @@ contextBridge.exposeInMainWorld('electronAPI', {
     // CEFR 分析
     analyzeCEFR: (text: string) => ipcRenderer.invoke('cefr:analyze', { text }),
     checkCEFR: () => ipcRenderer.invoke('cefr:check'),
+    parseSentence: (text: string) => ipcRenderer.invoke('cefr:parse', { text }),
     saveBookFile: (id: string, arrayBuffer: ArrayBuffer) => ipcRenderer.invoke('save-book-file', { id, arrayBuffer }),
     deleteBookFile: (id: string) => ipcRenderer.invoke('delete-book-file', id),
     // Backup & Restore
@@ -16,6 +16,7 @@ interface ElectronAPI {
     // CEFR 分析
     analyzeCEFR: (text: string) => Promise<{ success: boolean; data?: CefrAnalysisResult; error?: string }>;
     checkCEFR: () => Promise<{ success: boolean; cefrDictPath?: string; cefrDictSize?: number; error?: string }>;
+    parseSentence: (text: string) => Promise<{ success: boolean; data?: SentenceParse; error?: string }>;
     // New Book File Management
     saveBookFile: (id: string, arrayBuffer: ArrayBuffer) => Promise<{ success: boolean; path?: string; error?: string }>;
     deleteBookFile: (id: string) => Promise<{ success: boolean; error?: string }>;
@@ -67,6 +68,44 @@ interface CefrAnalysisResult {
     };
 }
 
+// 浅层句法分析结果（parse_sentence）
+interface ParseOffsets {
+    start: number;
+    end: number;
+    start_utf16: number;
+    end_utf16: number;
+}
+
+interface ParseChunk {
+    kind: 'NP' | 'VP' | 'PP' | 'ADJP' | 'ADVP';
+    start: number; // tokens[start..end]
+    end: number;
+    head: number;
+    text: string;
+    offsets: ParseOffsets;
+    children: ParseChunk[]; // PP 的宾语
+}
+
+interface ParseClause {
+    kind: 'main' | 'relative' | 'adverbial' | 'complement';
+    opener: number | null; // 引导词在 tokens 中的下标
+    start: number;
+    end: number;
+    text: string;
+    offsets: ParseOffsets;
+    children: ParseNode[];
+}
+
+type ParseNode =
+    | ({ type: 'clause' } & ParseClause)
+    | ({ type: 'chunk' } & ParseChunk)
+    | { type: 'token'; index: number };
+
+interface SentenceParse {
+    tokens: { text: string; tag: string; offsets: ParseOffsets }[];
+    clauses: ParseClause[];
+}
+
 interface Window {
     electronAPI: ElectronAPI;
 }